@@ -13,6 +13,7 @@
 //! - Double-line variants
 //! - Rounded corners
 //! - Dashed lines
+//! - Quadrant blocks (U+2596-U+259F) and sextants (U+1FB00-U+1FB3B)
 //!
 //! # Example
 //!
@@ -26,7 +27,10 @@
 //! }
 //! ```
 
-use gpui::{point, Bounds, Hsla, PathBuilder, Pixels, Point, Window, px};
+use gpui::{
+    point, quad, transparent_black, Bounds, Edges, Hsla, PathBuilder, Pixels, Point, Size,
+    Window, px,
+};
 
 /// Calculate line thicknesses rounded to integer pixels to avoid aliasing.
 fn calculate_thickness(cell_width: Pixels) -> (Pixels, Pixels) {
@@ -124,7 +128,7 @@ use LineWeight::*;
 #[inline]
 pub fn is_box_drawing_char(ch: char) -> bool {
     let code = ch as u32;
-    code >= 0x2500 && code <= 0x257F
+    (0x2500..=0x257F).contains(&code)
 }
 
 /// Returns the horizontal line weight if the character has a continuous horizontal line
@@ -162,7 +166,7 @@ pub fn extends_right(ch: char) -> bool {
 /// Returns `None` if the character is not a recognized box-drawing character.
 pub fn get_box_segments(ch: char) -> Option<BoxSegments> {
     let code = ch as u32;
-    if code < 0x2500 || code > 0x257F {
+    if !(0x2500..=0x257F).contains(&code) {
         return None;
     }
 
@@ -357,7 +361,7 @@ pub fn get_box_segments(ch: char) -> Option<BoxSegments> {
 #[inline]
 fn is_rounded_corner(ch: char) -> bool {
     let code = ch as u32;
-    code >= 0x256D && code <= 0x2570
+    (0x256D..=0x2570).contains(&code)
 }
 
 /// Draws a horizontal line spanning multiple cells.
@@ -794,6 +798,133 @@ fn draw_rounded_corner(
     }
 }
 
+/// A block mosaic character split into a grid of filled sub-cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockMosaic {
+    /// Number of sub-cell columns (always 2)
+    pub columns: usize,
+    /// Number of sub-cell rows (2 for quadrants, 3 for sextants)
+    pub rows: usize,
+    /// Filled sub-cells as a bitmask, row-major from the top-left (bit 0)
+    pub filled: u8,
+}
+
+impl BlockMosaic {
+    /// Returns true if the sub-cell at the given row and column is filled.
+    pub fn is_filled(&self, row: usize, col: usize) -> bool {
+        self.filled & (1 << (row * self.columns + col)) != 0
+    }
+}
+
+/// Returns true if the character is a quadrant block or a sextant.
+#[inline]
+pub fn is_block_mosaic_char(ch: char) -> bool {
+    get_block_mosaic(ch).is_some()
+}
+
+/// Returns the sub-cell layout for quadrant (U+2596-U+259F) and sextant
+/// (U+1FB00-U+1FB3B) characters.
+///
+/// Returns `None` for any other character.
+pub fn get_block_mosaic(ch: char) -> Option<BlockMosaic> {
+    let code = ch as u32;
+
+    // Quadrant bits: 1 = upper left, 2 = upper right, 4 = lower left, 8 = lower right
+    let quadrants = match code {
+        0x2596 => Some(0b0100), // ▖
+        0x2597 => Some(0b1000), // ▗
+        0x2598 => Some(0b0001), // ▘
+        0x2599 => Some(0b1101), // ▙
+        0x259A => Some(0b1001), // ▚
+        0x259B => Some(0b0111), // ▛
+        0x259C => Some(0b1011), // ▜
+        0x259D => Some(0b0010), // ▝
+        0x259E => Some(0b0110), // ▞
+        0x259F => Some(0b1110), // ▟
+        _ => None,
+    };
+    if let Some(filled) = quadrants {
+        return Some(BlockMosaic {
+            columns: 2,
+            rows: 2,
+            filled,
+        });
+    }
+
+    // Sextants enumerate every 2x3 pattern in order, except the empty and full
+    // cells and the two half blocks (patterns 21 and 42) which already exist
+    // as ▌ and ▐.
+    if (0x1FB00..=0x1FB3B).contains(&code) {
+        let mut filled = (code - 0x1FB00 + 1) as u8;
+        if filled >= 21 {
+            filled += 1;
+        }
+        if filled >= 42 {
+            filled += 1;
+        }
+        return Some(BlockMosaic {
+            columns: 2,
+            rows: 3,
+            filled,
+        });
+    }
+
+    None
+}
+
+/// Draws a quadrant or sextant character by filling its sub-cells.
+///
+/// Sub-cell edges are snapped to whole pixels so that neighbouring cells meet
+/// without seams or overlaps.
+///
+/// Returns `true` if the character was drawn, `false` if it's not a block mosaic.
+pub fn draw_block_mosaic(
+    ch: char,
+    bounds: Bounds<Pixels>,
+    color: Hsla,
+    window: &mut Window,
+) -> bool {
+    let Some(mosaic) = get_block_mosaic(ch) else {
+        return false;
+    };
+
+    let x_edge = |col: usize| {
+        (bounds.origin.x + bounds.size.width * (col as f32 / mosaic.columns as f32)).round()
+    };
+    let y_edge = |row: usize| {
+        (bounds.origin.y + bounds.size.height * (row as f32 / mosaic.rows as f32)).round()
+    };
+
+    for row in 0..mosaic.rows {
+        for col in 0..mosaic.columns {
+            if !mosaic.is_filled(row, col) {
+                continue;
+            }
+
+            let (left, right) = (x_edge(col), x_edge(col + 1));
+            let (top, bottom) = (y_edge(row), y_edge(row + 1));
+            let rect = Bounds {
+                origin: point(left, top),
+                size: Size {
+                    width: right - left,
+                    height: bottom - top,
+                },
+            };
+
+            window.paint_quad(quad(
+                rect,
+                px(0.0),
+                color,
+                Edges::<Pixels>::default(),
+                transparent_black(),
+                Default::default(),
+            ));
+        }
+    }
+
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -861,4 +992,46 @@ mod tests {
         assert!(get_box_segments('A').is_none());
         assert!(get_box_segments(' ').is_none());
     }
+
+    #[test]
+    fn test_quadrant_mosaic() {
+        let mosaic = get_block_mosaic('▚').unwrap();
+        assert_eq!((mosaic.columns, mosaic.rows), (2, 2));
+        assert!(mosaic.is_filled(0, 0));
+        assert!(!mosaic.is_filled(0, 1));
+        assert!(!mosaic.is_filled(1, 0));
+        assert!(mosaic.is_filled(1, 1));
+
+        let mosaic = get_block_mosaic('▟').unwrap();
+        assert!(!mosaic.is_filled(0, 0));
+        assert!(mosaic.is_filled(0, 1));
+        assert!(mosaic.is_filled(1, 0));
+        assert!(mosaic.is_filled(1, 1));
+    }
+
+    #[test]
+    fn test_sextant_mosaic() {
+        // U+1FB00: top-left sextant only
+        let mosaic = get_block_mosaic('\u{1FB00}').unwrap();
+        assert_eq!((mosaic.columns, mosaic.rows), (2, 3));
+        assert_eq!(mosaic.filled, 0b000001);
+
+        // U+1FB14 skips pattern 21 (left half block)
+        assert_eq!(get_block_mosaic('\u{1FB13}').unwrap().filled, 20);
+        assert_eq!(get_block_mosaic('\u{1FB14}').unwrap().filled, 22);
+
+        // U+1FB3B is the last sextant: everything except the top-left
+        let mosaic = get_block_mosaic('\u{1FB3B}').unwrap();
+        assert_eq!(mosaic.filled, 0b111110);
+        assert!(!mosaic.is_filled(0, 0));
+        assert!(mosaic.is_filled(2, 1));
+    }
+
+    #[test]
+    fn test_block_mosaic_invalid() {
+        assert!(!is_block_mosaic_char('█'));
+        assert!(!is_block_mosaic_char('▌'));
+        assert!(!is_block_mosaic_char('A'));
+        assert!(!is_block_mosaic_char('\u{1FB3C}'));
+    }
 }
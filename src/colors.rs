@@ -390,6 +390,71 @@ impl ColorPalette {
     pub fn cursor(&self) -> Hsla {
         self.cursor
     }
+
+    /// Finds the ANSI color (0-15) closest to an RGB color.
+    ///
+    /// Useful for downsampling true colors for consumers limited to 16
+    /// colors. Distance is a perceptually weighted RGB distance (the "redmean"
+    /// approximation), so the result depends on this palette's ANSI colors.
+    /// Ties go to the lower index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alacritty_terminal::vte::ansi::Rgb;
+    /// use gpui_terminal::ColorPalette;
+    ///
+    /// let palette = ColorPalette::default();
+    /// assert_eq!(palette.nearest_ansi(Rgb { r: 255, g: 0, b: 0 }), 1);
+    /// assert_eq!(palette.nearest_ansi(Rgb { r: 0, g: 0, b: 0 }), 0);
+    /// ```
+    pub fn nearest_ansi(&self, rgb: Rgb) -> usize {
+        nearest_color(&self.ansi_colors, rgb)
+    }
+
+    /// Finds the 256-color palette index closest to an RGB color.
+    ///
+    /// Works like [`nearest_ansi`](Self::nearest_ansi) over the full
+    /// 256-color palette, including the ANSI colors at indices 0-15.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use alacritty_terminal::vte::ansi::Rgb;
+    /// use gpui_terminal::ColorPalette;
+    ///
+    /// let palette = ColorPalette::default();
+    /// assert_eq!(palette.nearest_extended(Rgb { r: 255, g: 0, b: 0 }), 196);
+    /// ```
+    pub fn nearest_extended(&self, rgb: Rgb) -> usize {
+        nearest_color(&self.extended_colors, rgb)
+    }
+}
+
+/// Returns the index of the candidate with the smallest [`color_distance`] to `rgb`.
+fn nearest_color(candidates: &[Hsla], rgb: Rgb) -> usize {
+    candidates
+        .iter()
+        .map(|&candidate| color_distance(rgb, candidate))
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map_or(0, |(idx, _)| idx)
+}
+
+/// Squared "redmean" distance between two colors, a cheap approximation of
+/// perceived difference that weighs the channels by how red the colors are.
+fn color_distance(rgb: Rgb, other: Hsla) -> f32 {
+    let other = other.to_rgb();
+    let r1 = rgb.r as f32;
+    let r2 = other.r * 255.0;
+    let dr = r1 - r2;
+    let dg = rgb.g as f32 - other.g * 255.0;
+    let db = rgb.b as f32 - other.b * 255.0;
+
+    let red_mean = (r1 + r2) / 2.0;
+    (2.0 + red_mean / 256.0) * dr * dr
+        + 4.0 * dg * dg
+        + (2.0 + (255.0 - red_mean) / 256.0) * db * db
 }
 
 /// Converts an RGB color to GPUI's Hsla color format.
@@ -579,6 +644,22 @@ impl ColorPaletteBuilder {
         self
     }
 
+    /// Sets all 16 ANSI colors at once, ordered from black (0) to bright white (15).
+    pub fn ansi(mut self, colors: [(u8, u8, u8); 16]) -> Self {
+        for (idx, (r, g, b)) in colors.into_iter().enumerate() {
+            self.set_ansi_color(idx, r, g, b);
+        }
+        self
+    }
+
+    /// Sets the 8 bright colors (8-15) at once, ordered from bright black to bright white.
+    pub fn bright(mut self, colors: [(u8, u8, u8); 8]) -> Self {
+        for (idx, (r, g, b)) in colors.into_iter().enumerate() {
+            self.set_ansi_color(8 + idx, r, g, b);
+        }
+        self
+    }
+
     /// Sets an ANSI color by index (0-15).
     fn set_ansi_color(&mut self, idx: usize, r: u8, g: u8, b: u8) {
         let color = rgb_to_hsla(Rgb { r, g, b });
@@ -634,6 +715,88 @@ mod tests {
         assert_eq!(palette.extended_colors.len(), 256);
     }
 
+    #[test]
+    fn test_builder_ansi_array() {
+        let mut colors = [(0, 0, 0); 16];
+        colors[1] = (255, 0, 0);
+        colors[15] = (255, 255, 255);
+        let palette = ColorPaletteBuilder::new().ansi(colors).build();
+
+        assert_eq!(
+            palette.ansi_colors[1],
+            rgb_to_hsla(Rgb { r: 255, g: 0, b: 0 })
+        );
+        assert_eq!(palette.ansi_colors[15].l, 1.0);
+        assert_eq!(palette.extended_colors[0..16], palette.ansi_colors[..]);
+    }
+
+    #[test]
+    fn test_builder_bright_array() {
+        let default = ColorPalette::default();
+        let palette = ColorPaletteBuilder::new().bright([(0, 0, 255); 8]).build();
+
+        // The normal half is untouched
+        assert_eq!(palette.ansi_colors[0..8], default.ansi_colors[0..8]);
+        for idx in 8..16 {
+            assert_eq!(
+                palette.ansi_colors[idx],
+                rgb_to_hsla(Rgb { r: 0, g: 0, b: 255 })
+            );
+            assert_eq!(palette.extended_colors[idx], palette.ansi_colors[idx]);
+        }
+    }
+
+    #[test]
+    fn test_nearest_ansi() {
+        let palette = ColorPalette::default();
+        assert_eq!(palette.nearest_ansi(Rgb { r: 255, g: 0, b: 0 }), 1);
+        assert_eq!(palette.nearest_ansi(Rgb { r: 255, g: 64, b: 64 }), 9);
+        assert_eq!(palette.nearest_ansi(Rgb { r: 0, g: 0, b: 0 }), 0);
+        assert_eq!(
+            palette.nearest_ansi(Rgb {
+                r: 255,
+                g: 255,
+                b: 255
+            }),
+            15
+        );
+        assert_eq!(
+            palette.nearest_ansi(Rgb {
+                r: 0x72,
+                g: 0x9f,
+                b: 0xcf
+            }),
+            12
+        );
+
+        // Follows the palette's own colors
+        let palette = ColorPaletteBuilder::new().black(255, 0, 0).build();
+        assert_eq!(palette.nearest_ansi(Rgb { r: 255, g: 0, b: 0 }), 0);
+    }
+
+    #[test]
+    fn test_nearest_extended() {
+        let palette = ColorPalette::default();
+        assert_eq!(
+            palette.nearest_extended(Rgb {
+                r: 95,
+                g: 135,
+                b: 175
+            }),
+            67
+        );
+        assert_eq!(
+            palette.nearest_extended(Rgb {
+                r: 0x80,
+                g: 0x80,
+                b: 0x80
+            }),
+            244
+        );
+        // Exact matches with ANSI colors prefer the lower index
+        assert_eq!(palette.nearest_extended(Rgb { r: 0, g: 0, b: 0 }), 0);
+    }
+
     #[test]
     fn test_resolve_named_color() {
         use alacritty_terminal::vte::ansi::NamedColor;
@@ -0,0 +1,260 @@
+//! Detection of escape sequences the terminal ignores.
+//!
+//! alacritty's parser silently drops sequences it doesn't implement, which
+//! makes it hard to tell why a program renders wrong. An
+//! [`UnhandledSequenceScanner`] runs a second parser over the same bytes and
+//! reports every control sequence that alacritty's dispatch tables don't
+//! cover:
+//!
+//! - CSI sequences with an unknown final byte or intermediates, unknown
+//!   `CSI t` window operations, unknown cursor styles, and unknown ANSI or DEC
+//!   private modes in `CSI h`/`CSI l`
+//! - ESC sequences such as `ESC # 6` (double-width lines)
+//! - OSC commands other than the title, color, hyperlink, cursor, clipboard and
+//!   OSC 133 shell integration ones
+//! - DCS strings, none of which are implemented
+//!
+//! Sequences are reported in their 7-bit form, rebuilt from the parsed
+//! parameters, so they are equivalent to the bytes the program sent but not
+//! necessarily identical (e.g. C1 introducers are reported as `ESC` forms, and
+//! OSC strings always end in BEL). Sequences that are parsed but have
+//! malformed parameters are not reported, and DCS strings are cut off after
+//! [`MAX_DCS_LEN`] bytes.
+//!
+//! # Example
+//!
+//! ```
+//! use gpui_terminal::diagnostics::UnhandledSequenceScanner;
+//!
+//! let mut scanner = UnhandledSequenceScanner::default();
+//! let mut unhandled = Vec::new();
+//! scanner.advance(b"\x1b[1mbold\x1b#6\x1b]1;icon\x07", |sequence| {
+//!     unhandled.push(sequence.to_vec())
+//! });
+//! assert_eq!(unhandled, [b"\x1b#6".to_vec(), b"\x1b]1;icon\x07".to_vec()]);
+//! ```
+
+use alacritty_terminal::vte::{Params, Parser, Perform};
+
+/// Longest DCS string collected for a report, in bytes.
+pub const MAX_DCS_LEN: usize = 4096;
+
+/// ANSI modes alacritty implements (`CSI n h`).
+const ANSI_MODES: &[u16] = &[4, 20];
+
+/// DEC private modes alacritty implements (`CSI ? n h`).
+const PRIVATE_MODES: &[u16] = &[
+    1, 3, 6, 7, 12, 25, 1000, 1002, 1003, 1004, 1005, 1006, 1007, 1042, 1049, 2004, 2026,
+];
+
+/// OSC commands alacritty implements, plus OSC 133 which
+/// [`TerminalState`](crate::terminal::TerminalState) handles itself.
+const OSC_COMMANDS: &[&[u8]] = &[
+    b"0", b"2", b"4", b"8", b"10", b"11", b"12", b"22", b"50", b"52", b"104", b"110", b"111",
+    b"112", b"133",
+];
+
+/// Incremental scanner that reports the escape sequences the terminal ignores.
+///
+/// Like the shell mark scanner, it only observes the stream; the bytes must
+/// still be passed to the terminal parser. Sequences split across calls are
+/// reported once they complete.
+#[derive(Default)]
+pub struct UnhandledSequenceScanner {
+    parser: Parser,
+    collector: Collector,
+}
+
+impl UnhandledSequenceScanner {
+    /// Feed bytes, calling `report` with each unhandled sequence they complete.
+    pub fn advance(&mut self, bytes: &[u8], mut report: impl FnMut(&[u8])) {
+        self.parser.advance(&mut self.collector, bytes);
+        for sequence in self.collector.unhandled.drain(..) {
+            report(&sequence);
+        }
+    }
+}
+
+/// Parser callbacks that collect the sequences alacritty wouldn't dispatch.
+#[derive(Default)]
+struct Collector {
+    /// Unhandled sequences completed by the current `advance` call
+    unhandled: Vec<Vec<u8>>,
+
+    /// The DCS string being received, if any
+    dcs: Option<Vec<u8>>,
+}
+
+impl Perform for Collector {
+    fn hook(&mut self, params: &Params, intermediates: &[u8], _ignore: bool, action: char) {
+        let mut sequence = b"\x1bP".to_vec();
+        push_params(&mut sequence, params, intermediates, action);
+        self.dcs = Some(sequence);
+    }
+
+    fn put(&mut self, byte: u8) {
+        if let Some(dcs) = self.dcs.as_mut().filter(|dcs| dcs.len() < MAX_DCS_LEN) {
+            dcs.push(byte);
+        }
+    }
+
+    fn unhook(&mut self) {
+        if let Some(mut dcs) = self.dcs.take() {
+            dcs.extend_from_slice(b"\x1b\\");
+            self.unhandled.push(dcs);
+        }
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        let command = params.first().copied().unwrap_or_default();
+        if command.is_empty() || OSC_COMMANDS.contains(&command) {
+            return;
+        }
+
+        let mut sequence = b"\x1b]".to_vec();
+        sequence.extend_from_slice(&params.join(&b';'));
+        sequence.push(0x07);
+        self.unhandled.push(sequence);
+    }
+
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, action: char) {
+        if !ignore && csi_handled(params, intermediates, action) {
+            return;
+        }
+
+        let mut sequence = b"\x1b[".to_vec();
+        push_params(&mut sequence, params, intermediates, action);
+        self.unhandled.push(sequence);
+    }
+
+    fn esc_dispatch(&mut self, intermediates: &[u8], ignore: bool, byte: u8) {
+        if !ignore && esc_handled(intermediates, byte) {
+            return;
+        }
+
+        let mut sequence = vec![0x1b];
+        sequence.extend_from_slice(intermediates);
+        sequence.push(byte);
+        self.unhandled.push(sequence);
+    }
+}
+
+/// Whether alacritty dispatches a CSI sequence, following its `csi_dispatch`.
+fn csi_handled(params: &Params, intermediates: &[u8], action: char) -> bool {
+    let first = params
+        .iter()
+        .next()
+        .and_then(|param| param.first())
+        .copied();
+    let mut modes = params.iter().filter_map(|param| param.first().copied());
+
+    match (action, intermediates) {
+        ('@' | 'A' | 'B' | 'e' | 'b' | 'C' | 'a' | 'D' | 'd' | 'E' | 'F' | 'G' | '`', [])
+        | ('H' | 'f' | 'I' | 'L' | 'M' | 'P' | 'S' | 's' | 'T' | 'u' | 'X' | 'Z', [])
+        | ('g' | 'J' | 'K' | 'm' | 'n' | 'r', [])
+        | ('k', [b' '])
+        | ('m', [b'?'])
+        | ('p', [b'$'] | [b'?', b'$'])
+        | ('u', [b'?' | b'=' | b'>' | b'<']) => true,
+        ('h' | 'l', []) => modes.all(|mode| ANSI_MODES.contains(&mode)),
+        ('h' | 'l', [b'?']) => modes.all(|mode| PRIVATE_MODES.contains(&mode)),
+        ('c', _) => first.unwrap_or(0) == 0,
+        ('m', [b'>']) => first == Some(4),
+        ('q', [b' ']) => first.unwrap_or(0) <= 6,
+        ('t', []) => matches!(first.unwrap_or(1), 14 | 18 | 22 | 23),
+        ('W', [b'?']) => first == Some(5),
+        _ => false,
+    }
+}
+
+/// Whether alacritty dispatches an ESC sequence, following its `esc_dispatch`.
+fn esc_handled(intermediates: &[u8], byte: u8) -> bool {
+    matches!(
+        (byte, intermediates),
+        (b'B' | b'0', [] | [b'('] | [b')'] | [b'*'] | [b'+'])
+            | (
+                b'D' | b'E' | b'H' | b'M' | b'Z' | b'c' | b'7' | b'8' | b'=' | b'>' | b'\\',
+                []
+            )
+            | (b'8', [b'#'])
+    )
+}
+
+/// Append CSI/DCS parameters with their prefix and trailing intermediates and
+/// the final byte.
+fn push_params(sequence: &mut Vec<u8>, params: &Params, intermediates: &[u8], action: char) {
+    // Private markers (`<`, `=`, `>`, `?`) precede the parameters
+    let (prefix, suffix): (Vec<u8>, Vec<u8>) = intermediates
+        .iter()
+        .partition(|&&byte| (0x3c..=0x3f).contains(&byte));
+    sequence.extend_from_slice(&prefix);
+
+    // A lone zero is the default, which the parser also reports for no parameters
+    let default_only = params.len() == 1 && params.iter().next() == Some(&[0][..]);
+    for (i, param) in params.iter().enumerate().filter(|_| !default_only) {
+        if i > 0 {
+            sequence.push(b';');
+        }
+        for (j, subparam) in param.iter().enumerate() {
+            if j > 0 {
+                sequence.push(b':');
+            }
+            sequence.extend_from_slice(subparam.to_string().as_bytes());
+        }
+    }
+
+    sequence.extend_from_slice(&suffix);
+    let mut buf = [0; 4];
+    sequence.extend_from_slice(action.encode_utf8(&mut buf).as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(bytes: &[u8]) -> Vec<Vec<u8>> {
+        let mut scanner = UnhandledSequenceScanner::default();
+        let mut unhandled = Vec::new();
+        scanner.advance(bytes, |sequence| unhandled.push(sequence.to_vec()));
+        unhandled
+    }
+
+    #[test]
+    fn test_handled_sequences_are_not_reported() {
+        let output = b"\x1b[1;31mred\x1b[0m\x1b[2J\x1b[H\x1b[?1049h\x1b[?25l\x1b[6 q\
+            \x1b(B\x1b7\x1b8\x1b]0;title\x07\x1b]133;A\x1b\\\x1b[>4;2m\x1b[18t";
+        assert!(scan(output).is_empty());
+    }
+
+    #[test]
+    fn test_unhandled_sequences() {
+        assert_eq!(scan(b"\x1b[?9999h"), [b"\x1b[?9999h".to_vec()]);
+        assert_eq!(scan(b"\x1b[?1;9999l"), [b"\x1b[?1;9999l".to_vec()]);
+        assert_eq!(scan(b"\x1b[3h"), [b"\x1b[3h".to_vec()]);
+        assert_eq!(scan(b"\x1b[>c\x1b[5y"), [b"\x1b[5y".to_vec()]);
+        assert_eq!(scan(b"\x1b[8;24;80t"), [b"\x1b[8;24;80t".to_vec()]);
+        assert_eq!(scan(b"\x1b[4:3m\x1b[>1u\x1b[=1;2u"), Vec::<Vec<u8>>::new());
+        assert_eq!(scan(b"\x1b[2\"q"), [b"\x1b[2\"q".to_vec()]);
+        assert_eq!(scan(b"\x1b#6"), [b"\x1b#6".to_vec()]);
+        assert_eq!(
+            scan(b"\x1b]7;file:///tmp\x1b\\"),
+            [b"\x1b]7;file:///tmp\x07".to_vec()]
+        );
+        assert_eq!(scan(b"\x1bP$qm\x1b\\"), [b"\x1bP$qm\x1b\\".to_vec()]);
+
+        let long_dcs = [b"\x1bPq".as_slice(), &[b'#'; 10_000], b"\x1b\\"].concat();
+        assert_eq!(scan(&long_dcs)[0].len(), MAX_DCS_LEN + 2);
+    }
+
+    #[test]
+    fn test_split_sequence() {
+        let mut scanner = UnhandledSequenceScanner::default();
+        let mut unhandled = Vec::new();
+        scanner.advance(b"text\x1b[?99", |sequence| {
+            unhandled.push(sequence.to_vec())
+        });
+        assert!(unhandled.is_empty());
+        scanner.advance(b"99h", |sequence| unhandled.push(sequence.to_vec()));
+        assert_eq!(unhandled, [b"\x1b[?9999h".to_vec()]);
+    }
+}
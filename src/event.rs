@@ -20,12 +20,13 @@
 //! | `Event::Bell` | `Bell` | BEL character received |
 //! | `Event::Title(_)` | `Title(String)` | Title escape sequence (OSC 0/2) |
 //! | `Event::ClipboardStore(_, _)` | `ClipboardStore(String)` | Copy request (OSC 52) |
-//! | `Event::ClipboardLoad(_, _)` | `ClipboardLoad` | Paste request |
+//! | `Event::ClipboardLoad(_, _)` | `ClipboardLoad(ClipboardFormatter)` | Clipboard query (OSC 52 `?`) |
+//! | `Event::PtyWrite(_)` | `PtyWrite(String)` | Reply to a query, e.g. `CSI 18 t` |
 //! | `Event::Exit` | `Exit` | Terminal exited |
 //! | `Event::ChildExit(_)` | `Exit` | Child process exited |
 //! | `Event::ResetTitle` | `Title("")` | Reset to empty title |
 //!
-//! Events like `MouseCursorDirty`, `PtyWrite`, and `CursorBlinkingChange` are
+//! Events like `MouseCursorDirty`, `ColorRequest`, and `CursorBlinkingChange` are
 //! ignored as they're handled internally or not needed for GPUI integration.
 //!
 //! # Example
@@ -44,6 +45,8 @@
 //! [`EventListener`]: alacritty_terminal::event::EventListener
 
 use alacritty_terminal::event::{Event, EventListener};
+use std::fmt;
+use std::sync::Arc;
 use std::sync::mpsc::Sender;
 
 /// Events emitted by the terminal that the GPUI application cares about.
@@ -64,13 +67,41 @@ pub enum TerminalEvent {
     /// The terminal wants to store data to the clipboard.
     ClipboardStore(String),
 
-    /// The terminal wants to load data from the clipboard.
-    ClipboardLoad,
+    /// The terminal queried the clipboard contents (OSC 52 with a `?` payload).
+    ///
+    /// Reply by writing the formatted clipboard text back to the PTY.
+    ClipboardLoad(ClipboardFormatter),
+
+    /// The terminal answered a query, such as the text area size in
+    /// characters (`CSI 18 t`).
+    ///
+    /// The reply must be written back to the PTY as-is.
+    PtyWrite(String),
 
     /// The terminal process has exited.
     Exit,
 }
 
+/// Formats clipboard text as the reply to an OSC 52 clipboard query.
+///
+/// The reply is an OSC 52 sequence carrying the base64-encoded text, using the
+/// clipboard selector and string terminator of the original query.
+#[derive(Clone)]
+pub struct ClipboardFormatter(Arc<dyn Fn(&str) -> String + Sync + Send>);
+
+impl ClipboardFormatter {
+    /// Build the escape sequence to write back to the PTY for `text`.
+    pub fn format(&self, text: &str) -> String {
+        (self.0)(text)
+    }
+}
+
+impl fmt::Debug for ClipboardFormatter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ClipboardFormatter")
+    }
+}
+
 /// An event proxy that implements alacritty's EventListener trait.
 ///
 /// This struct forwards relevant terminal events to a channel that can be
@@ -135,17 +166,17 @@ impl EventListener for GpuiEventProxy {
                 // For simplicity, we ignore the clipboard type and just store the data
                 self.send(TerminalEvent::ClipboardStore(data));
             }
-            Event::ClipboardLoad(_clipboard_type, _format) => {
-                // For simplicity, we ignore the clipboard type and format
-                self.send(TerminalEvent::ClipboardLoad);
+            Event::ClipboardLoad(_clipboard_type, format) => {
+                // The formatter already encodes the queried clipboard type
+                self.send(TerminalEvent::ClipboardLoad(ClipboardFormatter(format)));
             }
             Event::Exit => {
                 self.send(TerminalEvent::Exit);
             }
             // Ignore events we don't care about
             Event::MouseCursorDirty => {}
-            Event::PtyWrite(ref _data) => {
-                // This is handled internally by alacritty
+            Event::PtyWrite(data) => {
+                self.send(TerminalEvent::PtyWrite(data));
             }
             Event::ColorRequest(ref _index, ref _format) => {
                 // Color requests are not commonly used
@@ -237,17 +268,33 @@ mod tests {
     #[test]
     fn test_clipboard_load_event() {
         use alacritty_terminal::term::ClipboardType;
-        use std::sync::Arc;
 
         let (tx, rx) = channel();
         let proxy = GpuiEventProxy::new(tx);
 
-        // ClipboardLoad requires a callback function
-        let callback = Arc::new(|s: &str| s.to_string());
+        // ClipboardLoad carries a formatter for the reply
+        let callback = Arc::new(|s: &str| format!("<{s}>"));
         proxy.send_event(Event::ClipboardLoad(ClipboardType::Clipboard, callback));
 
-        let event = rx.recv().unwrap();
-        assert!(matches!(event, TerminalEvent::ClipboardLoad));
+        match rx.recv().unwrap() {
+            TerminalEvent::ClipboardLoad(formatter) => {
+                assert_eq!(formatter.format("text"), "<text>")
+            }
+            _ => panic!("Expected ClipboardLoad event"),
+        }
+    }
+
+    #[test]
+    fn test_pty_write_event() {
+        let (tx, rx) = channel();
+        let proxy = GpuiEventProxy::new(tx);
+
+        proxy.send_event(Event::PtyWrite("\x1b[8;24;80t".to_string()));
+
+        match rx.recv().unwrap() {
+            TerminalEvent::PtyWrite(data) => assert_eq!(data, "\x1b[8;24;80t"),
+            _ => panic!("Expected PtyWrite event"),
+        }
     }
 
     #[test]
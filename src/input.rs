@@ -9,11 +9,13 @@
 //!
 //! | Key | Sequence | Notes |
 //! |-----|----------|-------|
-//! | Enter | `\r` (0x0D) | Carriage return |
+//! | Enter | `\r` (0x0D) | Carriage return, `\r\n` in LNM (see [`EnterMode`]) |
 //! | Escape | `\x1b` (0x1B) | ESC |
 //! | Backspace | `\x7f` (0x7F) | DEL |
+//! | Ctrl+Backspace | `\x17` (0x17) | Ctrl+W, erases a word (see [`WordErase`]) |
+//! | Alt+Backspace | `\x1b\x7f` | ESC DEL, erases a word in readline |
 //! | Tab | `\t` (0x09) | Horizontal tab |
-//! | Shift+Tab | `\x1b[Z` | Backtab |
+//! | Shift+Tab | `\x1b[Z` | Backtab, `\x1b[9;2u` with the kitty keyboard protocol (see [`tab_bytes`]) |
 //! | Space | ` ` (0x20) | Space |
 //! | Ctrl+Space | `\x00` | NUL |
 //!
@@ -66,6 +68,51 @@
 //! The [`TermMode`] flags affect key sequences:
 //!
 //! - **APP_CURSOR**: Changes arrow key sequences from CSI to SS3 format
+//! - **APP_KEYPAD**: Changes keypad operator keys to SS3 sequences
+//! - **LINE_FEED_NEW_LINE**: Enter sends `\r\n` (LNM, set with `CSI 20 h`)
+//!
+//! ## Keypad Keys
+//!
+//! | Key | Numeric Mode | App Keypad Mode |
+//! |-----|--------------|-----------------|
+//! | `*` | `*` | `\x1bOj` |
+//! | `+` | `+` | `\x1bOk` |
+//! | `,` | `,` | `\x1bOl` |
+//! | `-` | `-` | `\x1bOm` |
+//! | `.` | `.` | `\x1bOn` |
+//! | `/` | `/` | `\x1bOo` |
+//!
+//! GPUI reports keypad digits and Enter with the same key names as the main
+//! keyboard, so those always send their regular bytes.
+//!
+//! ## modifyOtherKeys
+//!
+//! Programs can ask for modified keys to be reported unambiguously with xterm's
+//! `CSI > 4 ; level m`. [`modify_other_keys_to_bytes`] then encodes them as
+//! `CSI 27 ; modifier ; code ~`, where `modifier` is 1 plus Shift (1), Alt (2)
+//! and Ctrl (4):
+//!
+//! | Combination | Level 1 | Level 2 |
+//! |-------------|---------|---------|
+//! | Ctrl+Enter | `\x1b[27;5;13~` | `\x1b[27;5;13~` |
+//! | Ctrl+I | `\t` (legacy) | `\x1b[27;5;105~` |
+//! | Alt+X | `\x1bx` (legacy) | `\x1b[27;3;120~` |
+//! | Shift+A | `A` (legacy) | `A` (legacy) |
+//!
+//! # Pasting
+//!
+//! [`paste_to_bytes`] prepares pasted text. With bracketed paste (DECSET 2004)
+//! it is wrapped in `\x1b[200~` and `\x1b[201~`, and any `\x1b[201~` inside
+//! the text is removed so it can't end the paste early and have the rest run
+//! as typed input. Without bracketed paste the text can optionally be
+//! sanitized: control characters other than `\n` and `\t`, including ESC,
+//! are dropped so pasted escape sequences can't drive the terminal.
+//!
+//! # Clipboard Shortcuts
+//!
+//! [`clipboard_shortcut`] recognizes the usual Linux terminal bindings,
+//! Ctrl+Shift+C to copy the selection and Ctrl+Shift+V to paste. Plain Ctrl+C
+//! and Ctrl+V still send their control characters.
 //!
 //! # Example
 //!
@@ -84,7 +131,148 @@
 //! ```
 
 use alacritty_terminal::term::TermMode;
-use gpui::Keystroke;
+use alacritty_terminal::vte::ansi::ModifyOtherKeys;
+use gpui::{Keystroke, Modifiers};
+
+/// The line ending sent for the Enter key.
+///
+/// Terminals traditionally send a carriage return, which the PTY's line
+/// discipline translates as needed. Hosts that talk to something other than a
+/// PTY, such as line-based protocols, may need a different ending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EnterMode {
+    /// Carriage return (`\r`)
+    #[default]
+    Cr,
+    /// Line feed (`\n`)
+    Lf,
+    /// Carriage return and line feed (`\r\n`)
+    CrLf,
+}
+
+/// Get the bytes to send for Enter.
+///
+/// When the program enabled line feed/new line mode (LNM, `CSI 20 h`), Enter
+/// sends `\r\n` as the program asked, whatever `enter_mode` says.
+///
+/// # Arguments
+///
+/// * `enter_mode` - The configured line ending
+/// * `mode` - The current terminal mode
+///
+/// # Examples
+///
+/// ```
+/// use alacritty_terminal::term::TermMode;
+/// use gpui_terminal::input::{EnterMode, enter_bytes};
+///
+/// assert_eq!(enter_bytes(EnterMode::Lf, TermMode::empty()), b"\n");
+/// assert_eq!(enter_bytes(EnterMode::Lf, TermMode::LINE_FEED_NEW_LINE), b"\r\n");
+/// ```
+pub fn enter_bytes(enter_mode: EnterMode, mode: TermMode) -> &'static [u8] {
+    if mode.contains(TermMode::LINE_FEED_NEW_LINE) {
+        return b"\r\n";
+    }
+    match enter_mode {
+        EnterMode::Cr => b"\r",
+        EnterMode::Lf => b"\n",
+        EnterMode::CrLf => b"\r\n",
+    }
+}
+
+/// The word-erase sequence sent for Ctrl+Backspace.
+///
+/// There is no standard byte for it, so shells differ in what they bind:
+/// readline and zsh erase a word on Ctrl+W and on ESC DEL, while some setups
+/// expect the plain DEL a bare Backspace sends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WordErase {
+    /// Ctrl+W (`\x17`), the terminal driver's WERASE character
+    #[default]
+    CtrlW,
+    /// ESC DEL (`\x1b\x7f`), the same as Alt+Backspace
+    EscDel,
+    /// DEL (`\x7f`), the same as a bare Backspace
+    Del,
+}
+
+/// Get the bytes to send for Backspace with the given modifiers.
+///
+/// Ctrl+Backspace sends the configured word-erase sequence, Alt+Backspace
+/// sends ESC DEL, and Backspace otherwise sends DEL.
+///
+/// # Arguments
+///
+/// * `modifiers` - The modifiers held with Backspace
+/// * `word_erase` - What Ctrl+Backspace sends
+///
+/// # Examples
+///
+/// ```
+/// use gpui::Keystroke;
+/// use gpui_terminal::input::{WordErase, backspace_bytes};
+///
+/// let keystroke = Keystroke::parse("ctrl-backspace").unwrap();
+/// assert_eq!(backspace_bytes(&keystroke.modifiers, WordErase::CtrlW), b"\x17");
+/// assert_eq!(backspace_bytes(&keystroke.modifiers, WordErase::EscDel), b"\x1b\x7f");
+/// ```
+pub fn backspace_bytes(modifiers: &Modifiers, word_erase: WordErase) -> &'static [u8] {
+    if modifiers.control {
+        match word_erase {
+            WordErase::CtrlW => b"\x17",
+            WordErase::EscDel => b"\x1b\x7f",
+            WordErase::Del => b"\x7f",
+        }
+    } else if modifiers.alt {
+        b"\x1b\x7f"
+    } else {
+        b"\x7f"
+    }
+}
+
+/// Get the bytes to send for Tab with the given modifiers.
+///
+/// Tab sends `\t` and Shift+Tab the backtab sequence `\x1b[Z`, unless the
+/// program enabled the kitty keyboard protocol (`CSI > flags u`):
+///
+/// - With "report all keys as escape codes" every Tab is sent as `CSI 9 u`,
+///   e.g. `\x1b[9;2u` for Shift+Tab
+/// - With "disambiguate escape codes" only Tab with Ctrl or Alt is, as the
+///   protocol keeps Tab and Shift+Tab on their legacy bytes in that mode
+///
+/// # Arguments
+///
+/// * `modifiers` - The modifiers held with Tab
+/// * `mode` - The current terminal mode
+///
+/// # Examples
+///
+/// ```
+/// use alacritty_terminal::term::TermMode;
+/// use gpui::Keystroke;
+/// use gpui_terminal::input::tab_bytes;
+///
+/// let keystroke = Keystroke::parse("shift-tab").unwrap();
+/// assert_eq!(tab_bytes(&keystroke.modifiers, TermMode::empty()), b"\x1b[Z");
+/// assert_eq!(
+///     tab_bytes(&keystroke.modifiers, TermMode::REPORT_ALL_KEYS_AS_ESC),
+///     b"\x1b[9;2u"
+/// );
+/// ```
+pub fn tab_bytes(modifiers: &Modifiers, mode: TermMode) -> Vec<u8> {
+    let modifier =
+        1 + modifiers.shift as u8 + 2 * modifiers.alt as u8 + 4 * modifiers.control as u8;
+    let csi_u = mode.contains(TermMode::REPORT_ALL_KEYS_AS_ESC)
+        || (mode.contains(TermMode::DISAMBIGUATE_ESC_CODES)
+            && (modifiers.alt || modifiers.control));
+
+    match (csi_u, modifier) {
+        (true, 1) => b"\x1b[9u".to_vec(),
+        (true, _) => format!("\x1b[9;{modifier}u").into_bytes(),
+        (false, _) if modifiers.shift => b"\x1b[Z".to_vec(),
+        (false, _) => b"\t".to_vec(),
+    }
+}
 
 /// Convert a GPUI keystroke to terminal escape sequence bytes.
 ///
@@ -123,16 +311,12 @@ pub fn keystroke_to_bytes(keystroke: &Keystroke, mode: TermMode) -> Option<Vec<u
             }
             return Some(b" ".to_vec());
         }
-        "enter" => return Some(b"\r".to_vec()),
+        "enter" => return Some(enter_bytes(EnterMode::Cr, mode).to_vec()),
         "escape" => return Some(b"\x1b".to_vec()),
-        "backspace" => return Some(b"\x7f".to_vec()),
-        "tab" => {
-            // Shift+Tab sends a different sequence
-            if keystroke.modifiers.shift {
-                return Some(b"\x1b[Z".to_vec());
-            }
-            return Some(b"\t".to_vec());
+        "backspace" => {
+            return Some(backspace_bytes(&keystroke.modifiers, WordErase::default()).to_vec());
         }
+        "tab" => return Some(tab_bytes(&keystroke.modifiers, mode)),
 
         // Arrow keys - check APP_CURSOR mode
         "up" => {
@@ -182,6 +366,11 @@ pub fn keystroke_to_bytes(keystroke: &Keystroke, mode: TermMode) -> Option<Vec<u
         "f11" => return Some(b"\x1b[23~".to_vec()),
         "f12" => return Some(b"\x1b[24~".to_vec()),
 
+        // Keypad operators - check APP_KEYPAD mode
+        key @ ("add" | "subtract" | "multiply" | "divide" | "decimal" | "separator") => {
+            return keypad_to_bytes(key, mode);
+        }
+
         _ => {}
     }
 
@@ -258,6 +447,184 @@ pub fn keystroke_to_bytes(keystroke: &Keystroke, mode: TermMode) -> Option<Vec<u
     None
 }
 
+/// Encode a keystroke for xterm's modifyOtherKeys mode.
+///
+/// Returns the `CSI 27 ; modifier ; code ~` sequence for modified keys that
+/// produce an ASCII character (including Enter, Tab, Backspace, Escape and
+/// Space), or `None` if the key should use its regular encoding from
+/// [`keystroke_to_bytes`]. Level 1 ([`ModifyOtherKeys::EnableExceptWellDefined`])
+/// keeps the legacy bytes for combinations that already have a well-known
+/// meaning, such as Ctrl+letter, Shift+Tab, Ctrl+Space and Alt+key. Level 2
+/// ([`ModifyOtherKeys::EnableAll`]) only keeps them for shifted characters.
+///
+/// # Arguments
+///
+/// * `keystroke` - The GPUI keystroke to convert
+/// * `level` - The modifyOtherKeys level requested by the program
+///
+/// # Examples
+///
+/// ```
+/// use gpui::Keystroke;
+/// use alacritty_terminal::vte::ansi::ModifyOtherKeys;
+/// use gpui_terminal::input::modify_other_keys_to_bytes;
+///
+/// let keystroke = Keystroke::parse("ctrl-enter").unwrap();
+/// let bytes = modify_other_keys_to_bytes(&keystroke, ModifyOtherKeys::EnableAll);
+/// assert_eq!(bytes, Some(b"\x1b[27;5;13~".to_vec()));
+/// ```
+pub fn modify_other_keys_to_bytes(
+    keystroke: &Keystroke,
+    level: ModifyOtherKeys,
+) -> Option<Vec<u8>> {
+    if level == ModifyOtherKeys::Reset {
+        return None;
+    }
+
+    let modifiers = &keystroke.modifiers;
+    let code = match keystroke.key.as_str() {
+        "enter" => 13,
+        "tab" => 9,
+        "backspace" => 127,
+        "escape" => 27,
+        "space" => 32,
+        key if key.len() == 1 && key.is_ascii() => {
+            let ch = key.as_bytes()[0];
+            if modifiers.shift {
+                // Report the shifted character, e.g. 65 for Ctrl+Shift+A
+                match keystroke.key_char.as_deref() {
+                    Some(key_char) if key_char.len() == 1 && key_char.is_ascii() => {
+                        key_char.as_bytes()[0]
+                    }
+                    _ => ch.to_ascii_uppercase(),
+                }
+            } else {
+                ch
+            }
+        }
+        _ => return None,
+    };
+
+    let (shift, alt, control) = (modifiers.shift, modifiers.alt, modifiers.control);
+    let only_shift = shift && !alt && !control;
+    let only_control = control && !shift && !alt;
+    let only_alt = alt && !shift && !control;
+    let printable = (32..127).contains(&code);
+
+    let well_defined = match level {
+        ModifyOtherKeys::EnableAll => only_shift && printable,
+        _ => {
+            only_alt
+                || (only_shift && (printable || code == 9))
+                || ((only_shift || only_control) && (64..=127).contains(&code))
+                || (only_control && code == 32)
+        }
+    };
+    if !(shift || alt || control) || well_defined {
+        return None;
+    }
+
+    let modifier = 1 + shift as u8 + 2 * alt as u8 + 4 * control as u8;
+    Some(format!("\x1b[27;{};{}~", modifier, code).into_bytes())
+}
+
+/// Get the bytes to send for pasted text.
+///
+/// # Arguments
+///
+/// * `text` - The pasted text
+/// * `bracketed` - Whether the program enabled bracketed paste
+/// * `sanitize` - Drop control characters other than `\n` and `\t` when
+///   bracketed paste is off
+///
+/// # Examples
+///
+/// ```
+/// use gpui_terminal::input::paste_to_bytes;
+///
+/// assert_eq!(paste_to_bytes("ls\x1b[201~", true, true), b"\x1b[200~ls\x1b[201~");
+/// assert_eq!(paste_to_bytes("a\x1b[31mb\n", false, true), b"a[31mb\n");
+/// assert_eq!(paste_to_bytes("a\x1b[31mb\n", false, false), b"a\x1b[31mb\n");
+/// ```
+pub fn paste_to_bytes(text: &str, bracketed: bool, sanitize: bool) -> Vec<u8> {
+    if bracketed {
+        // Removing a marker can join its neighbours into a new one
+        let mut text = text.to_string();
+        while text.contains("\x1b[201~") {
+            text = text.replace("\x1b[201~", "");
+        }
+        return [b"\x1b[200~", text.as_bytes(), b"\x1b[201~"].concat();
+    }
+
+    if sanitize {
+        text.chars()
+            .filter(|&c| !c.is_control() || matches!(c, '\n' | '\t'))
+            .collect::<String>()
+            .into_bytes()
+    } else {
+        text.as_bytes().to_vec()
+    }
+}
+
+/// A clipboard action bound to a key combination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardShortcut {
+    /// Copy the selection (Ctrl+Shift+C)
+    Copy,
+    /// Paste from the clipboard (Ctrl+Shift+V)
+    Paste,
+}
+
+/// Get the clipboard action a keystroke is bound to, if any.
+///
+/// Only Ctrl+Shift+C and Ctrl+Shift+V match; any other modifier held with
+/// them, such as Alt, lets the keystroke through to the program.
+///
+/// # Examples
+///
+/// ```
+/// use gpui::Keystroke;
+/// use gpui_terminal::input::{ClipboardShortcut, clipboard_shortcut};
+///
+/// let keystroke = Keystroke::parse("ctrl-shift-c").unwrap();
+/// assert_eq!(clipboard_shortcut(&keystroke), Some(ClipboardShortcut::Copy));
+/// assert_eq!(clipboard_shortcut(&Keystroke::parse("ctrl-c").unwrap()), None);
+/// ```
+pub fn clipboard_shortcut(keystroke: &Keystroke) -> Option<ClipboardShortcut> {
+    let modifiers = &keystroke.modifiers;
+    if !(modifiers.control && modifiers.shift) || modifiers.alt || modifiers.platform {
+        return None;
+    }
+
+    match keystroke.key.to_ascii_lowercase().as_str() {
+        "c" => Some(ClipboardShortcut::Copy),
+        "v" => Some(ClipboardShortcut::Paste),
+        _ => None,
+    }
+}
+
+/// Convert a keypad operator key to bytes.
+///
+/// In application keypad mode (DECKPAM) the keys send SS3 sequences; otherwise
+/// they send the character printed on the key.
+fn keypad_to_bytes(key: &str, mode: TermMode) -> Option<Vec<u8>> {
+    let (code, ch) = match key {
+        "multiply" => (b'j', b'*'),
+        "add" => (b'k', b'+'),
+        "separator" => (b'l', b','),
+        "subtract" => (b'm', b'-'),
+        "decimal" => (b'n', b'.'),
+        "divide" => (b'o', b'/'),
+        _ => return None,
+    };
+
+    if mode.contains(TermMode::APP_KEYPAD) {
+        Some(vec![b'\x1b', b'O', code])
+    } else {
+        Some(vec![ch])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -269,6 +636,56 @@ mod tests {
         assert_eq!(bytes, Some(b"\r".to_vec()));
     }
 
+    #[test]
+    fn test_enter_modes() {
+        let normal = TermMode::empty();
+        assert_eq!(enter_bytes(EnterMode::Cr, normal), b"\r");
+        assert_eq!(enter_bytes(EnterMode::Lf, normal), b"\n");
+        assert_eq!(enter_bytes(EnterMode::CrLf, normal), b"\r\n");
+        assert_eq!(EnterMode::default(), EnterMode::Cr);
+
+        // LNM overrides the configured ending
+        let lnm = TermMode::LINE_FEED_NEW_LINE;
+        for enter_mode in [EnterMode::Cr, EnterMode::Lf, EnterMode::CrLf] {
+            assert_eq!(enter_bytes(enter_mode, lnm), b"\r\n");
+        }
+
+        let keystroke = Keystroke::parse("enter").unwrap();
+        assert_eq!(keystroke_to_bytes(&keystroke, lnm), Some(b"\r\n".to_vec()));
+    }
+
+    #[test]
+    fn test_paste_with_escape() {
+        let text = "echo hi\x1b[2J\r\nrm -rf /\x07\u{9b}31m\tok";
+
+        // Bracketed paste leaves the text to the program
+        let mut expected = b"\x1b[200~".to_vec();
+        expected.extend_from_slice(text.as_bytes());
+        expected.extend_from_slice(b"\x1b[201~");
+        assert_eq!(paste_to_bytes(text, true, true), expected);
+
+        // Without it, control characters (ESC, CR, BEL and C1 CSI) are dropped
+        assert_eq!(
+            paste_to_bytes(text, false, true),
+            b"echo hi[2J\nrm -rf /31m\tok"
+        );
+        assert_eq!(paste_to_bytes(text, false, false), text.as_bytes());
+    }
+
+    #[test]
+    fn test_paste_end_marker() {
+        assert_eq!(
+            paste_to_bytes("safe\x1b[201~\nrm -rf ~\n", true, false),
+            b"\x1b[200~safe\nrm -rf ~\n\x1b[201~"
+        );
+
+        // A marker hidden inside another can't reassemble
+        assert_eq!(
+            paste_to_bytes("a\x1b[20\x1b[201~1~b", true, false),
+            b"\x1b[200~ab\x1b[201~"
+        );
+    }
+
     #[test]
     fn test_escape_key() {
         let keystroke = Keystroke::parse("escape").unwrap();
@@ -283,6 +700,31 @@ mod tests {
         assert_eq!(bytes, Some(b"\x7f".to_vec()));
     }
 
+    #[test]
+    fn test_word_erase_keys() {
+        let ctrl = Keystroke::parse("ctrl-backspace").unwrap();
+        assert_eq!(
+            keystroke_to_bytes(&ctrl, TermMode::empty()),
+            Some(b"\x17".to_vec())
+        );
+        assert_eq!(backspace_bytes(&ctrl.modifiers, WordErase::CtrlW), b"\x17");
+        assert_eq!(
+            backspace_bytes(&ctrl.modifiers, WordErase::EscDel),
+            b"\x1b\x7f"
+        );
+        assert_eq!(backspace_bytes(&ctrl.modifiers, WordErase::Del), b"\x7f");
+
+        // Alt+Backspace doesn't depend on the setting
+        let alt = Keystroke::parse("alt-backspace").unwrap();
+        assert_eq!(
+            keystroke_to_bytes(&alt, TermMode::empty()),
+            Some(b"\x1b\x7f".to_vec())
+        );
+        for word_erase in [WordErase::CtrlW, WordErase::EscDel, WordErase::Del] {
+            assert_eq!(backspace_bytes(&alt.modifiers, word_erase), b"\x1b\x7f");
+        }
+    }
+
     #[test]
     fn test_tab_key() {
         let keystroke = Keystroke::parse("tab").unwrap();
@@ -297,6 +739,73 @@ mod tests {
         assert_eq!(bytes, Some(b"\x1b[Z".to_vec()));
     }
 
+    #[test]
+    fn test_clipboard_shortcut() {
+        let shortcut = |keys: &str| clipboard_shortcut(&Keystroke::parse(keys).unwrap());
+
+        assert_eq!(shortcut("ctrl-shift-c"), Some(ClipboardShortcut::Copy));
+        assert_eq!(shortcut("ctrl-shift-v"), Some(ClipboardShortcut::Paste));
+        assert_eq!(shortcut("ctrl-shift-C"), Some(ClipboardShortcut::Copy));
+
+        // Anything else goes to the program
+        for keys in [
+            "ctrl-c",
+            "ctrl-v",
+            "shift-c",
+            "ctrl-alt-shift-c",
+            "cmd-ctrl-shift-v",
+            "ctrl-shift-x",
+        ] {
+            assert_eq!(shortcut(keys), None, "{keys}");
+        }
+        assert_eq!(
+            keystroke_to_bytes(&Keystroke::parse("ctrl-c").unwrap(), TermMode::empty()),
+            Some(vec![0x03])
+        );
+    }
+
+    #[test]
+    fn test_tab_keyboard_modes() {
+        let tab = |keys: &str, mode| {
+            let keystroke = Keystroke::parse(keys).unwrap();
+            keystroke_to_bytes(&keystroke, mode).unwrap()
+        };
+
+        // Legacy, and modes that don't concern Tab
+        for mode in [
+            TermMode::empty(),
+            TermMode::APP_CURSOR | TermMode::APP_KEYPAD,
+        ] {
+            assert_eq!(tab("tab", mode), b"\t");
+            assert_eq!(tab("shift-tab", mode), b"\x1b[Z");
+            assert_eq!(tab("ctrl-tab", mode), b"\t");
+        }
+
+        // Kitty: disambiguate keeps Tab and Shift+Tab legacy
+        let disambiguate = TermMode::DISAMBIGUATE_ESC_CODES;
+        assert_eq!(tab("tab", disambiguate), b"\t");
+        assert_eq!(tab("shift-tab", disambiguate), b"\x1b[Z");
+        assert_eq!(tab("ctrl-tab", disambiguate), b"\x1b[9;5u");
+        assert_eq!(tab("alt-shift-tab", disambiguate), b"\x1b[9;4u");
+
+        // Kitty: report all keys as escape codes
+        let all_keys = TermMode::DISAMBIGUATE_ESC_CODES | TermMode::REPORT_ALL_KEYS_AS_ESC;
+        assert_eq!(tab("tab", all_keys), b"\x1b[9u");
+        assert_eq!(tab("shift-tab", all_keys), b"\x1b[9;2u");
+        assert_eq!(tab("ctrl-shift-tab", all_keys), b"\x1b[9;6u");
+
+        // modifyOtherKeys keeps backtab at level 1 only
+        let shift_tab = Keystroke::parse("shift-tab").unwrap();
+        assert_eq!(
+            modify_other_keys_to_bytes(&shift_tab, ModifyOtherKeys::EnableExceptWellDefined),
+            None
+        );
+        assert_eq!(
+            modify_other_keys_to_bytes(&shift_tab, ModifyOtherKeys::EnableAll),
+            Some(b"\x1b[27;2;9~".to_vec())
+        );
+    }
+
     #[test]
     fn test_arrow_keys_normal_mode() {
         let mode = TermMode::empty();
@@ -331,6 +840,37 @@ mod tests {
         assert_eq!(keystroke_to_bytes(&left, mode), Some(b"\x1bOD".to_vec()));
     }
 
+    #[test]
+    fn test_keypad_keys_numeric_mode() {
+        let mode = TermMode::empty();
+
+        let add = Keystroke::parse("add").unwrap();
+        assert_eq!(keystroke_to_bytes(&add, mode), Some(b"+".to_vec()));
+
+        let decimal = Keystroke::parse("decimal").unwrap();
+        assert_eq!(keystroke_to_bytes(&decimal, mode), Some(b".".to_vec()));
+    }
+
+    #[test]
+    fn test_keypad_keys_app_keypad_mode() {
+        let mode = TermMode::APP_KEYPAD;
+
+        let multiply = Keystroke::parse("multiply").unwrap();
+        assert_eq!(
+            keystroke_to_bytes(&multiply, mode),
+            Some(b"\x1bOj".to_vec())
+        );
+
+        let subtract = Keystroke::parse("subtract").unwrap();
+        assert_eq!(
+            keystroke_to_bytes(&subtract, mode),
+            Some(b"\x1bOm".to_vec())
+        );
+
+        let divide = Keystroke::parse("divide").unwrap();
+        assert_eq!(keystroke_to_bytes(&divide, mode), Some(b"\x1bOo".to_vec()));
+    }
+
     #[test]
     fn test_navigation_keys() {
         let mode = TermMode::empty();
@@ -429,4 +969,70 @@ mod tests {
         let space = Keystroke::parse("space").unwrap();
         assert_eq!(keystroke_to_bytes(&space, mode), Some(b" ".to_vec()));
     }
+
+    #[test]
+    fn test_modify_other_keys() {
+        let level1 = ModifyOtherKeys::EnableExceptWellDefined;
+        let level2 = ModifyOtherKeys::EnableAll;
+
+        // Ctrl+Enter is ambiguous with Enter in the legacy encoding
+        let ctrl_enter = Keystroke::parse("ctrl-enter").unwrap();
+        for level in [level1, level2] {
+            assert_eq!(
+                modify_other_keys_to_bytes(&ctrl_enter, level),
+                Some(b"\x1b[27;5;13~".to_vec())
+            );
+        }
+        assert_eq!(
+            modify_other_keys_to_bytes(&ctrl_enter, ModifyOtherKeys::Reset),
+            None
+        );
+
+        let shift_enter = Keystroke::parse("shift-enter").unwrap();
+        assert_eq!(
+            modify_other_keys_to_bytes(&shift_enter, level1),
+            Some(b"\x1b[27;2;13~".to_vec())
+        );
+
+        // Well-defined combinations keep their legacy bytes at level 1 only
+        let ctrl_i = Keystroke::parse("ctrl-i").unwrap();
+        assert_eq!(modify_other_keys_to_bytes(&ctrl_i, level1), None);
+        assert_eq!(
+            modify_other_keys_to_bytes(&ctrl_i, level2),
+            Some(b"\x1b[27;5;105~".to_vec())
+        );
+
+        let alt_x = Keystroke::parse("alt-x").unwrap();
+        assert_eq!(modify_other_keys_to_bytes(&alt_x, level1), None);
+        assert_eq!(
+            modify_other_keys_to_bytes(&alt_x, level2),
+            Some(b"\x1b[27;3;120~".to_vec())
+        );
+
+        let shift_tab = Keystroke::parse("shift-tab").unwrap();
+        assert_eq!(modify_other_keys_to_bytes(&shift_tab, level1), None);
+
+        // Ctrl+digit has no legacy encoding
+        let ctrl_1 = Keystroke::parse("ctrl-1").unwrap();
+        assert_eq!(
+            modify_other_keys_to_bytes(&ctrl_1, level1),
+            Some(b"\x1b[27;5;49~".to_vec())
+        );
+
+        let ctrl_shift_a = Keystroke::parse("ctrl-shift-a").unwrap();
+        assert_eq!(
+            modify_other_keys_to_bytes(&ctrl_shift_a, level1),
+            Some(b"\x1b[27;6;65~".to_vec())
+        );
+
+        // Unmodified and shifted characters are never encoded
+        for key in ["a", "enter", "shift-a"] {
+            let keystroke = Keystroke::parse(key).unwrap();
+            assert_eq!(modify_other_keys_to_bytes(&keystroke, level2), None);
+        }
+
+        // Keys with their own modifier encoding are left alone
+        let ctrl_up = Keystroke::parse("ctrl-up").unwrap();
+        assert_eq!(modify_other_keys_to_bytes(&ctrl_up, level2), None);
+    }
 }
@@ -66,6 +66,7 @@
 //!             line_height_multiplier: 1.0,
 //!             padding: Edges::all(px(8.0)),
 //!             colors: ColorPalette::default(),
+//!             ..Default::default()
 //!         };
 //!
 //!         // 5. Create resize callback to sync PTY dimensions
@@ -169,6 +170,9 @@
 //!
 //!     // Color scheme
 //!     colors: ColorPalette::default(),
+//!
+//!     // Slant italics manually for fonts without an italic face
+//!     synthesize_italic: true,
 //! };
 //! ```
 //!
@@ -261,6 +265,24 @@
 //! - **Bell**: `with_bell_callback` - Terminal bell (BEL character)
 //! - **Title**: `with_title_callback` - Window title changes (OSC 0/2)
 //! - **Clipboard**: `with_clipboard_store_callback` - Clipboard write requests (OSC 52)
+//! - **Clipboard queries**: `with_clipboard_load_callback` - Clipboard read requests (OSC 52,
+//!   requires `allow_clipboard_read`)
+//! - **Updates**: `with_update_callback` - Grid content changed by new output
+//! - **Layout**: `with_layout_callback` - Grid size and the pixel rectangle its cells cover
+//! - **Diagnostics**: `with_unhandled_sequence_callback` - Escape sequences the terminal
+//!   ignores, for debugging
+//! - **Scrollback log**: `with_scrollback_evict_callback` - Lines dropped from the full
+//!   scrollback, to persist them
+//! - **Recording**: `with_input_tap` and `with_output_tap` - Raw bytes written to and read
+//!   from the PTY
+//! - **Filtering**: `with_output_filter` - Rewrite or drop output before it is parsed
+//! - **Theming**: `with_color_change_callback` - Palette, foreground, background and cursor
+//!   colors changed by the program
+//! - **Hover**: `with_hover_callback` - The cell under the mouse pointer, when it changes
+//!
+//! Each `with_*` method sets a single callback. For bell, title, clipboard store,
+//! exit and update events, `add_*_callback` registers further listeners that all
+//! run in order, so separate subsystems can observe the same event.
 //!
 //! ## Dynamic Configuration
 //!
@@ -287,11 +309,16 @@
 //! | Application cursor mode | ✅ Full support |
 //! | Function keys (F1-F12) | ✅ Full support |
 //! | Mouse click reporting | 🔄 Partial (framework ready) |
-//! | Mouse selection | 🔄 Planned |
-//! | Scrollback | 🔄 Planned |
+//! | Mouse selection | ✅ Full support |
+//! | Keyboard selection | ✅ Arrow keys, via `enter_selection_mode` |
+//! | Scrollback | ✅ Wheel, trackpad and Shift+PageUp/PageDown |
 //! | Clipboard (OSC 52) | ✅ Callback support |
+//! | Copy/paste keys | ✅ Ctrl+Shift+C/V, via `clipboard_shortcuts` |
 //! | Title changes (OSC 0/2) | ✅ Callback support |
-//! | Bell (BEL) | ✅ Callback support |
+//! | Bell (BEL) | ✅ Callback support, optional system beep (`audible-bell` feature) |
+//! | Shell integration (OSC 133) | ✅ Prompt marks and jumps |
+//! | Synchronized output (DECSET 2026) | ✅ Full support |
+//! | Zoom | ✅ Ctrl+scroll and touchpad pinch (as Ctrl+scroll) |
 //!
 //! ## Platform Support
 //!
@@ -310,6 +337,7 @@
 //! | [`input`] | Keyboard to escape sequence conversion |
 //! | [`mouse`] | Mouse event handling and reporting |
 //! | [`clipboard`] | System clipboard integration ([`Clipboard`]) |
+//! | [`shell_integration`] | OSC 133 prompt marks ([`ShellMark`]) |
 //!
 //! ## Troubleshooting
 //!
@@ -340,20 +368,34 @@
 pub mod box_drawing;
 pub mod clipboard;
 pub mod colors;
+pub mod diagnostics;
 pub mod event;
 pub mod input;
 pub mod mouse;
 pub mod render;
+pub mod shell_integration;
 pub mod terminal;
 pub mod view;
 
 // Re-export main types for convenience
 pub use clipboard::Clipboard;
 pub use colors::{ColorPalette, ColorPaletteBuilder};
-pub use event::{GpuiEventProxy, TerminalEvent};
-pub use render::TerminalRenderer;
-pub use terminal::TerminalState;
+pub use event::{ClipboardFormatter, GpuiEventProxy, TerminalEvent};
+pub use input::{ClipboardShortcut, EnterMode, WordErase};
+pub use render::{CursorState, GlyphRenderer, TerminalRenderer};
+pub use shell_integration::ShellMark;
+pub use terminal::{ScreenDump, StyledCell, TerminalState};
 pub use view::{
-    BellCallback, ClipboardStoreCallback, ExitCallback, KeyHandler, ResizeCallback, TerminalConfig,
-    TerminalView, TitleCallback,
+    BellCallback, ClipboardLoadCallback, ClipboardStoreCallback, ColorChangeCallback,
+    DEFAULT_WORD_SEPARATORS, Decorations, DynamicColor, ExitCallback, HoverCallback, KeyHandler,
+    LayoutCallback, OutputFilter, PtyTap, ResizeCallback, ScrollOnOutput, ScrollbackEvictCallback,
+    TerminalConfig, TerminalView, TitleCallback, UnhandledSequenceCallback, UpdateCallback,
 };
+
+// Re-export alacritty types used by the public API, so consumers can match on
+// modes, scroll the display and tune the emulator without depending on
+// alacritty_terminal directly
+pub use alacritty_terminal::grid::Scroll;
+pub use alacritty_terminal::term::Config as TermConfig;
+pub use alacritty_terminal::term::TermMode;
+pub use alacritty_terminal::vte::ansi::{CursorShape, CursorStyle, Rgb};
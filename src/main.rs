@@ -28,20 +28,13 @@ impl TerminalApp {
         // Check for Ctrl++ or Ctrl+= (increase font size)
         if keystroke.modifiers.control && (keystroke.key == "+" || keystroke.key == "=") {
             self.terminal.update(cx, |terminal, cx| {
-                let mut config = terminal.config().clone();
-                config.font_size += px(1.0);
-                terminal.update_config(config, cx);
+                terminal.adjust_font_size(px(1.0), cx);
             });
             cx.stop_propagation();
         } else if keystroke.modifiers.control && keystroke.key == "-" {
             // Check for Ctrl+- (decrease font size)
             self.terminal.update(cx, |terminal, cx| {
-                let mut config = terminal.config().clone();
-                // Don't go below 6px font size
-                if config.font_size > px(6.0) {
-                    config.font_size -= px(1.0);
-                    terminal.update_config(config, cx);
-                }
+                terminal.adjust_font_size(px(-1.0), cx);
             });
             cx.stop_propagation();
         }
@@ -136,6 +129,7 @@ fn main() -> Result<()> {
                 line_height_multiplier: 1.0,
                 padding: Edges::all(px(8.0)),
                 colors,
+                ..Default::default()
             };
 
             // Create resize callback that notifies the PTY
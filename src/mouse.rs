@@ -86,6 +86,8 @@ pub enum SelectionType {
     Word,
     /// Line-based selection (triple click).
     Line,
+    /// Rectangular selection spanning the same columns on every row (Alt+drag).
+    Block,
 }
 
 /// Represents a text selection in the terminal.
@@ -132,13 +134,88 @@ impl Selection {
     ///
     /// `true` if the point is within the selection, `false` otherwise.
     pub fn contains(&self, point: AlacPoint) -> bool {
-        let (start, end) = if self.start < self.end {
+        match self.selection_type {
+            SelectionType::Block => {
+                let (min_line, max_line) = self.line_range();
+                let min_col = self.start.column.min(self.end.column);
+                let max_col = self.start.column.max(self.end.column);
+
+                point.line >= min_line
+                    && point.line <= max_line
+                    && point.column >= min_col
+                    && point.column <= max_col
+            }
+            SelectionType::Line => {
+                let (min_line, max_line) = self.line_range();
+                point.line >= min_line && point.line <= max_line
+            }
+            SelectionType::Simple | SelectionType::Word => {
+                let (start, end) = self.ordered();
+                point >= start && point <= end
+            }
+        }
+    }
+
+    /// Compute the columns the selection covers on a given line.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The grid line to check
+    /// * `num_cols` - The number of columns in the grid
+    ///
+    /// # Returns
+    ///
+    /// `Some((start_col, end_col))` with an exclusive end column, or `None` if
+    /// the selection does not touch the line.
+    pub fn columns_on_line(&self, line: Line, num_cols: usize) -> Option<(usize, usize)> {
+        let (min_line, max_line) = self.line_range();
+        if num_cols == 0 || line < min_line || line > max_line {
+            return None;
+        }
+
+        let last_col = num_cols - 1;
+        let (start_col, end_col) = match self.selection_type {
+            SelectionType::Block => (
+                self.start.column.0.min(self.end.column.0),
+                self.start.column.0.max(self.end.column.0),
+            ),
+            SelectionType::Line => (0, last_col),
+            SelectionType::Simple | SelectionType::Word => {
+                let (start, end) = self.ordered();
+                let start_col = if line == start.line {
+                    start.column.0
+                } else {
+                    0
+                };
+                let end_col = if line == end.line {
+                    end.column.0
+                } else {
+                    last_col
+                };
+                (start_col, end_col)
+            }
+        };
+
+        let start_col = start_col.min(last_col);
+        let end_col = end_col.min(last_col);
+        (start_col <= end_col).then_some((start_col, end_col + 1))
+    }
+
+    /// The selection endpoints ordered from first to last in reading order.
+    fn ordered(&self) -> (AlacPoint, AlacPoint) {
+        if self.start < self.end {
             (self.start, self.end)
         } else {
             (self.end, self.start)
-        };
+        }
+    }
 
-        point >= start && point <= end
+    /// The first and last line touched by the selection.
+    fn line_range(&self) -> (Line, Line) {
+        (
+            self.start.line.min(self.end.line),
+            self.start.line.max(self.end.line),
+        )
     }
 }
 
@@ -562,6 +639,65 @@ mod tests {
         assert!(selection.contains(AlacPoint::new(Line(7), Column(20))));
     }
 
+    #[test]
+    fn test_selection_contains_block() {
+        // Dragged from bottom-right to top-left
+        let selection = Selection::new(
+            AlacPoint::new(Line(7), Column(20)),
+            AlacPoint::new(Line(5), Column(10)),
+            SelectionType::Block,
+        );
+
+        // Corners and interior
+        assert!(selection.contains(AlacPoint::new(Line(5), Column(10))));
+        assert!(selection.contains(AlacPoint::new(Line(5), Column(20))));
+        assert!(selection.contains(AlacPoint::new(Line(7), Column(10))));
+        assert!(selection.contains(AlacPoint::new(Line(6), Column(15))));
+
+        // Columns outside the rectangle are excluded even on middle rows
+        assert!(!selection.contains(AlacPoint::new(Line(6), Column(9))));
+        assert!(!selection.contains(AlacPoint::new(Line(6), Column(21))));
+        assert!(!selection.contains(AlacPoint::new(Line(5), Column(25))));
+
+        // Rows outside the rectangle
+        assert!(!selection.contains(AlacPoint::new(Line(4), Column(15))));
+        assert!(!selection.contains(AlacPoint::new(Line(8), Column(15))));
+    }
+
+    #[test]
+    fn test_selection_contains_line() {
+        let selection = Selection::new(
+            AlacPoint::new(Line(5), Column(10)),
+            AlacPoint::new(Line(6), Column(3)),
+            SelectionType::Line,
+        );
+
+        assert!(selection.contains(AlacPoint::new(Line(5), Column(0))));
+        assert!(selection.contains(AlacPoint::new(Line(6), Column(79))));
+        assert!(!selection.contains(AlacPoint::new(Line(7), Column(0))));
+    }
+
+    #[test]
+    fn test_selection_columns_on_line() {
+        let simple = Selection::new(
+            AlacPoint::new(Line(1), Column(4)),
+            AlacPoint::new(Line(3), Column(2)),
+            SelectionType::Simple,
+        );
+        assert_eq!(simple.columns_on_line(Line(0), 10), None);
+        assert_eq!(simple.columns_on_line(Line(1), 10), Some((4, 10)));
+        assert_eq!(simple.columns_on_line(Line(2), 10), Some((0, 10)));
+        assert_eq!(simple.columns_on_line(Line(3), 10), Some((0, 3)));
+
+        let block = Selection::new(
+            AlacPoint::new(Line(1), Column(6)),
+            AlacPoint::new(Line(3), Column(2)),
+            SelectionType::Block,
+        );
+        assert_eq!(block.columns_on_line(Line(2), 10), Some((2, 7)));
+        assert_eq!(block.columns_on_line(Line(4), 10), None);
+    }
+
     #[test]
     fn test_mouse_button_report_left_click() {
         let point = AlacPoint::new(Line(5), Column(10));
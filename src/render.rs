@@ -46,6 +46,31 @@
 //! The `line_height_multiplier` (default 1.0) can be adjusted to add extra
 //! vertical space if needed for specific fonts.
 //!
+//! # Double Underline and SGR 21
+//!
+//! ECMA-48 defines SGR 21 as doubly underlined, but xterm and alacritty
+//! treat it as "bold off", and the renderer draws whatever alacritty stored:
+//! `CSI 21 m` clears bold and never underlines. Double underline is selected
+//! with the colon form `CSI 4:2 m`, which sets the `DOUBLE_UNDERLINE` flag and
+//! is drawn as two lines at the bottom of the text area, each as thick as the
+//! font-scaled underline and one thickness apart.
+//!
+//! # Unsupported Attributes
+//!
+//! Only attributes stored in alacritty's cell [`Flags`] can be drawn. The VTE
+//! parser drops SGR 53 (overline) and alacritty has no overline flag, so
+//! overlined text renders without the line.
+//!
+//! The protected attribute set with DECSCA (`CSI 1 " q`) has no flag either.
+//! alacritty also ignores selective erase (DECSED `CSI ? J` and DECSEL
+//! `CSI ? K`), so a selective erase leaves every cell unchanged instead of
+//! clearing the unprotected ones. Nothing is ever drawn differently for
+//! protected cells, and text extraction sees exactly what is on screen.
+//!
+//! The same applies to line attributes: alacritty ignores double-width
+//! (DECDWL, `ESC # 6`) and double-height (DECDHL, `ESC # 3`/`ESC # 4`) lines and
+//! keeps no per-row attribute, so those rows render at normal size.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -63,16 +88,71 @@
 use crate::box_drawing;
 use crate::colors::ColorPalette;
 use crate::event::GpuiEventProxy;
+use crate::mouse::Selection;
+use crate::terminal::TAB_WIDTH;
 use alacritty_terminal::grid::Dimensions;
 use alacritty_terminal::index::{Column, Line, Point as AlacPoint};
-use alacritty_terminal::term::Term;
 use alacritty_terminal::term::cell::{Cell, Flags};
 use alacritty_terminal::term::color::Colors;
-use alacritty_terminal::vte::ansi::Color;
+use alacritty_terminal::term::search::Match;
+use alacritty_terminal::term::{Term, TermMode, point_to_viewport};
+use alacritty_terminal::vte::ansi::{Color, CursorShape};
 use gpui::{
-    App, Bounds, Edges, Font, FontFeatures, FontStyle, FontWeight, Hsla, Pixels, Point,
-    SharedString, Size, TextRun, UnderlineStyle, Window, px, quad, transparent_black,
+    App, BorderStyle, Bounds, ContentMask, Edges, Font, FontFeatures, FontStyle, FontWeight, Hsla,
+    Pixels, Point, ShapedLine, SharedString, Size, TextRun, UnderlineStyle, Window, px, quad,
+    transparent_black,
 };
+use std::sync::Arc;
+
+/// Horizontal shear applied to synthesized italics (roughly a 12° slant).
+const ITALIC_SKEW: f32 = 0.2;
+
+/// Number of horizontal bands used to approximate the italic shear.
+const ITALIC_SLICES: usize = 8;
+
+/// Font size, in pixels, per pixel of underline thickness.
+///
+/// 14px text gets the usual 1px underline.
+const UNDERLINE_THICKNESS_SIZE: f32 = 14.0;
+
+/// Estimated cell width as a fraction of the font size, until the font is measured.
+///
+/// 0.6 is the typical em-width ratio of monospace fonts.
+pub const DEFAULT_CELL_WIDTH_RATIO: f32 = 0.6;
+
+/// Estimated cell height as a fraction of the font size, until the font is measured.
+pub const DEFAULT_CELL_HEIGHT_RATIO: f32 = 1.4;
+
+/// Callback type for custom character rendering.
+///
+/// Consulted for every non-blank character in the text pass, before the
+/// character is shaped with the terminal font.
+///
+/// # Arguments
+///
+/// * `ch` - The character in the cell
+/// * `bounds` - The cell's bounds in window coordinates
+/// * `color` - The resolved foreground color for the cell
+/// * `window` - The GPUI window to paint into
+///
+/// # Returns
+///
+/// * `true` - The callback drew the character; skip default rendering
+/// * `false` - Render the character normally
+///
+/// # Example
+///
+/// ```ignore
+/// terminal.with_glyph_renderer(|ch, bounds, color, window| {
+///     // Draw a private-use prompt glyph as a filled square
+///     if ch == '\u{e0b0}' {
+///         window.paint_quad(fill(bounds, color));
+///         return true;
+///     }
+///     false
+/// });
+/// ```
+pub type GlyphRenderer = Box<dyn Fn(char, Bounds<Pixels>, Hsla, &mut Window) -> bool>;
 
 /// A batched run of text with consistent styling.
 ///
@@ -119,6 +199,9 @@ pub struct BackgroundRect {
     /// Row position
     pub row: usize,
 
+    /// Number of rows covered, starting at `row`
+    pub rows: usize,
+
     /// Background color
     pub color: Hsla,
 }
@@ -127,14 +210,32 @@ impl BackgroundRect {
     /// Check if this rectangle can be merged with another.
     ///
     /// Two rectangles can be merged if they:
-    /// - Are on the same row
+    /// - Are on the same rows
     /// - Have the same color
     /// - Are horizontally adjacent
     fn can_merge_with(&self, other: &Self) -> bool {
-        self.row == other.row && self.color == other.color && self.end_col == other.start_col
+        self.row == other.row
+            && self.rows == other.rows
+            && self.color == other.color
+            && self.end_col == other.start_col
     }
 }
 
+/// How prominently the cursor is drawn.
+///
+/// Lets a multi-pane layout tell apart the focused terminal, terminals the user
+/// was working in a moment ago, and terminals that have been left alone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CursorState {
+    /// Solid block (the terminal has keyboard focus).
+    #[default]
+    Focused,
+    /// Dashed outline (unfocused, but used recently).
+    Recent,
+    /// Dimmed block (unfocused and idle).
+    Idle,
+}
+
 /// Terminal renderer with font settings and cell dimensions.
 ///
 /// This struct manages the rendering of terminal content, including text,
@@ -183,8 +284,72 @@ pub struct TerminalRenderer {
     /// Multiplier for line height to accommodate tall glyphs
     pub line_height_multiplier: f32,
 
+    /// Extra width added to the measured cell width
+    pub letter_spacing: Pixels,
+
+    /// Device pixels per logical pixel, which column positions are snapped to
+    pub scale_factor: f32,
+
     /// Color palette for resolving terminal colors
     pub palette: ColorPalette,
+
+    /// Slant italic text manually when the font family has no italic face
+    pub synthesize_italic: bool,
+
+    /// Draw box-drawing characters programmatically instead of using font glyphs
+    pub use_builtin_box_drawing: bool,
+
+    /// Redraw the character under a block cursor in the cell's background color
+    pub cursor_inverts_text: bool,
+
+    /// Color for the full-bounds fill behind the grid (padding and gaps).
+    /// `None` uses the terminal's default background.
+    pub forced_background: Option<Hsla>,
+
+    /// How the cursor is drawn
+    pub cursor_state: CursorState,
+
+    /// Whether the cursor is in the visible phase of its blink
+    pub cursor_visible: bool,
+
+    /// Outline every cell with faint lines to debug font metrics and alignment
+    pub draw_cell_grid: bool,
+
+    /// Columns to mark with a faint vertical line behind the text
+    pub column_guides: Vec<usize>,
+
+    /// Mark spaces and tabs with faint glyphs
+    pub show_whitespace: bool,
+
+    /// Color the cursor with the foreground of the cell under it instead of
+    /// the palette's cursor color
+    pub cursor_follows_fg: bool,
+
+    /// Thickness of the bar (beam) cursor
+    pub bar_cursor_width: Pixels,
+
+    /// Opacity (0.0-1.0) of a background-colored overlay painted over the
+    /// whole terminal, used to dim inactive panes. `None` paints no overlay.
+    pub dim_overlay: Option<f32>,
+
+    /// Label the bottom-right corner with `[process exited]`
+    pub exit_indicator: bool,
+
+    /// Optional hook that draws characters instead of the font
+    pub glyph_renderer: Option<Arc<GlyphRenderer>>,
+
+    /// Current text selection, in grid coordinates
+    pub selection: Option<Selection>,
+
+    /// Cursor of the keyboard selection mode, in grid coordinates, outlined
+    /// in the cursor color
+    pub selection_cursor: Option<AlacPoint>,
+
+    /// Search matches to highlight behind the text
+    pub search_matches: Vec<Match>,
+
+    /// The focused search match, highlighted more strongly than the others
+    pub active_match: Option<Match>,
 }
 
 impl TerminalRenderer {
@@ -217,9 +382,8 @@ impl TerminalRenderer {
         palette: ColorPalette,
     ) -> Self {
         // Default cell dimensions - will be measured on first paint
-        // Using 0.6 as approximate em-width ratio for monospace fonts
-        let cell_width = font_size * 0.6;
-        let cell_height = font_size * 1.4; // Line height with some spacing
+        let cell_width = font_size * DEFAULT_CELL_WIDTH_RATIO;
+        let cell_height = font_size * DEFAULT_CELL_HEIGHT_RATIO; // Line height with some spacing
 
         Self {
             font_family,
@@ -227,7 +391,27 @@ impl TerminalRenderer {
             cell_width,
             cell_height,
             line_height_multiplier,
+            letter_spacing: px(0.0),
+            scale_factor: 1.0,
             palette,
+            synthesize_italic: false,
+            use_builtin_box_drawing: true,
+            cursor_inverts_text: true,
+            forced_background: None,
+            cursor_state: CursorState::Focused,
+            cursor_visible: true,
+            draw_cell_grid: false,
+            column_guides: Vec::new(),
+            show_whitespace: false,
+            cursor_follows_fg: false,
+            bar_cursor_width: px(2.0),
+            dim_overlay: None,
+            exit_indicator: false,
+            glyph_renderer: None,
+            selection: None,
+            selection_cursor: None,
+            search_matches: Vec::new(),
+            active_match: None,
         }
     }
 
@@ -236,11 +420,15 @@ impl TerminalRenderer {
     /// This method measures the actual width and height of characters
     /// using the GPUI text system. It uses the '│' (BOX DRAWINGS LIGHT VERTICAL)
     /// character which spans the full cell height in properly designed terminal fonts.
+    /// The cell width includes [`letter_spacing`](Self::letter_spacing), and
+    /// the window's [`scale_factor`](Self::scale_factor) is picked up too.
     ///
     /// # Arguments
     ///
     /// * `window` - The GPUI window for text system access
     pub fn measure_cell(&mut self, window: &mut Window) {
+        self.scale_factor = window.scale_factor();
+
         // Measure using '│' (U+2502, BOX DRAWINGS LIGHT VERTICAL)
         // This character spans the full cell height in terminal fonts, making it
         // ideal for measuring exact cell dimensions used by TUIs
@@ -266,9 +454,9 @@ impl TerminalRenderer {
             .text_system()
             .shape_line("│".into(), self.font_size, &[text_run], None);
 
-        // Get the width from the shaped line
+        // Get the width from the shaped line, widened by the letter spacing
         if shaped.width > px(0.0) {
-            self.cell_width = shaped.width;
+            self.cell_width = shaped.width + self.letter_spacing;
         }
 
         // Calculate height from ascent + descent with optional multiplier
@@ -278,6 +466,28 @@ impl TerminalRenderer {
         }
     }
 
+    /// Compute the left edge of a column, snapped to the device pixel grid.
+    ///
+    /// Every pass positions columns with this, and takes widths as the
+    /// difference of two column edges, so cells tile exactly. See
+    /// [`column_x`].
+    fn column_x(&self, origin_x: Pixels, col: usize) -> Pixels {
+        column_x(origin_x, self.cell_width, col, self.scale_factor)
+    }
+
+    /// The bounds of `cols` cells starting at column `col` of the row at `y`,
+    /// with both edges snapped by [`column_x`](Self::column_x).
+    fn cell_bounds(&self, origin_x: Pixels, col: usize, cols: usize, y: Pixels) -> Bounds<Pixels> {
+        let x = self.column_x(origin_x, col);
+        Bounds {
+            origin: Point { x, y },
+            size: Size {
+                width: self.column_x(origin_x, col + cols) - x,
+                height: self.cell_height,
+            },
+        }
+    }
+
     /// Layout cells into batched text runs and background rects for a single row.
     ///
     /// This method processes a row of terminal cells and groups adjacent cells
@@ -320,12 +530,8 @@ impl TerminalRenderer {
             let italic = cell.flags.contains(Flags::ITALIC);
             let underline = cell.flags.contains(Flags::UNDERLINE);
 
-            // Get the character (or space if empty)
-            let ch = if cell.c == ' ' || cell.c == '\0' {
-                ' '
-            } else {
-                cell.c
-            };
+            // Get the character (or space if empty) with any combining marks
+            let text = cell_text(&cell);
 
             // Handle background rectangles
             if let Some(ref mut bg_rect) = current_bg {
@@ -339,6 +545,7 @@ impl TerminalRenderer {
                         start_col: col,
                         end_col: col + 1,
                         row,
+                        rows: 1,
                         color: bg_color,
                     });
                 }
@@ -348,6 +555,7 @@ impl TerminalRenderer {
                     start_col: col,
                     end_col: col + 1,
                     row,
+                    rows: 1,
                     color: bg_color,
                 });
             }
@@ -361,12 +569,12 @@ impl TerminalRenderer {
                     && run.underline == underline
                 {
                     // Extend current run
-                    run.text.push(ch);
+                    run.text.push_str(&text);
                 } else {
                     // Save current run and start new one
                     text_runs.push(run.clone());
                     current_run = Some(BatchedTextRun {
-                        text: ch.to_string(),
+                        text: text.clone(),
                         start_col: col,
                         row,
                         fg_color,
@@ -379,7 +587,7 @@ impl TerminalRenderer {
             } else {
                 // Start new run
                 current_run = Some(BatchedTextRun {
-                    text: ch.to_string(),
+                    text,
                     start_col: col,
                     row,
                     fg_color,
@@ -438,346 +646,1278 @@ impl TerminalRenderer {
         merged
     }
 
-    /// Paint terminal content to the window.
+    /// Merge same-colored background rects on consecutive rows.
     ///
-    /// This is the main rendering method that draws the terminal grid,
-    /// including backgrounds, text, and cursor.
+    /// Rects spanning the same columns with the same color are combined into
+    /// one taller rect, so a solid block (e.g. a colored status area or a TUI
+    /// panel) is painted as a single quad instead of one per row.
     ///
     /// # Arguments
     ///
-    /// * `bounds` - The bounding box to render within
-    /// * `padding` - Padding around the terminal content
-    /// * `term` - The terminal state
-    /// * `window` - The GPUI window
-    /// * `cx` - The application context
-    pub fn paint(
-        &self,
-        bounds: Bounds<Pixels>,
-        padding: Edges<Pixels>,
-        term: &Term<GpuiEventProxy>,
-        window: &mut Window,
-        _cx: &mut App,
-    ) {
-        // Get terminal dimensions
-        let grid = term.grid();
-        let num_lines = grid.screen_lines();
-        let num_cols = grid.columns();
-        let colors = term.colors();
-
-        // Calculate default background color
-        let default_bg = self.palette.resolve(
-            Color::Named(alacritty_terminal::vte::ansi::NamedColor::Background),
-            colors,
-        );
-
-        // Paint default background (covers full bounds including padding)
-        window.paint_quad(quad(
-            bounds,
-            px(0.0),
-            default_bg,
-            Edges::<Pixels>::default(),
-            transparent_black(),
-            Default::default(),
-        ));
-
-        // Calculate origin offset (content starts after padding)
-        let origin = Point {
-            x: bounds.origin.x + padding.left,
-            y: bounds.origin.y + padding.top,
-        };
-
-        // Iterate over visible lines
-        for line_idx in 0..num_lines {
-            let line = Line(line_idx as i32);
-
-            // Collect cells for this line
-            let cells: Vec<(usize, Cell)> = (0..num_cols)
-                .map(|col_idx| {
-                    let col = Column(col_idx);
-                    let point = AlacPoint::new(line, col);
-                    let cell = grid[point].clone();
-                    (col_idx, cell)
-                })
-                .collect();
-
-            // Layout the row for backgrounds
-            let (backgrounds, _) = self.layout_row(line_idx, cells.iter().cloned(), colors);
-
-            // Paint backgrounds
-            for bg_rect in backgrounds {
-                // Skip if it's the default background color
-                if bg_rect.color == default_bg {
-                    continue;
-                }
-
-                let x = origin.x + self.cell_width * (bg_rect.start_col as f32);
-                let y = origin.y + self.cell_height * (bg_rect.row as f32);
-                let width = self.cell_width * ((bg_rect.end_col - bg_rect.start_col) as f32);
-                let height = self.cell_height;
-
-                let rect_bounds = Bounds {
-                    origin: Point { x, y },
-                    size: Size { width, height },
-                };
-
-                window.paint_quad(quad(
-                    rect_bounds,
-                    px(0.0),
-                    bg_rect.color,
-                    Edges::<Pixels>::default(),
-                    transparent_black(),
-                    Default::default(),
-                ));
-            }
-
-            // Calculate vertical offset to center text in cell
-            // The multiplier adds extra height; we want to distribute it evenly top/bottom
-            let base_height = self.cell_height / self.line_height_multiplier;
-            let vertical_offset = (self.cell_height - base_height) / 2.0;
-
-            let y_base = origin.y + self.cell_height * (line_idx as f32);
-            let cy = y_base + self.cell_height / 2.0;
-
-            // Use cells vec for multiple passes (already collected above)
-            let cells_vec = &cells;
-
-            // First pass: find and draw horizontal spans of box-drawing characters
-            // This draws continuous lines across multiple cells to avoid gaps
-            let mut processed_horizontal: std::collections::HashSet<usize> = std::collections::HashSet::new();
-
-            let mut i = 0;
-            while i < cells_vec.len() {
-                let (col_idx, ref cell) = cells_vec[i];
-                let ch = cell.c;
-
-                // Check if this starts a horizontal span
-                if let Some(weight) = box_drawing::get_horizontal_weight(ch) {
-                    let fg_color = self.palette.resolve(cell.fg, colors);
-                    let start_col = col_idx;
-                    let mut end_col = col_idx;
-
-                    // Look ahead for consecutive cells with same horizontal weight
-                    let mut j = i + 1;
-                    while j < cells_vec.len() {
-                        let (next_col, ref next_cell) = cells_vec[j];
-                        // Must be adjacent
-                        if next_col != end_col + 1 {
-                            break;
-                        }
-                        // Must have same horizontal weight and same color
-                        let next_fg = self.palette.resolve(next_cell.fg, colors);
-                        if box_drawing::get_horizontal_weight(next_cell.c) == Some(weight)
-                            && next_fg == fg_color
-                        {
-                            end_col = next_col;
-                            j += 1;
-                        } else {
-                            break;
-                        }
-                    }
-
-                    // Draw the horizontal span
-                    let start_x = origin.x + self.cell_width * (start_col as f32);
-                    let end_x = origin.x + self.cell_width * ((end_col + 1) as f32);
-
-                    box_drawing::draw_horizontal_span(
-                        start_x,
-                        end_x,
-                        cy,
-                        weight,
-                        self.cell_width,
-                        fg_color,
-                        window,
-                    );
-
-                    // Mark these columns as having horizontal drawn
-                    for col in start_col..=end_col {
-                        processed_horizontal.insert(col);
-                    }
+    /// * `rects` - Single-row rectangles, ordered by row
+    ///
+    /// # Returns
+    ///
+    /// A new vector with merged rectangles
+    fn merge_backgrounds_vertically(&self, rects: Vec<BackgroundRect>) -> Vec<BackgroundRect> {
+        let mut merged: Vec<BackgroundRect> = Vec::new();
+        // Indices into `merged` of the rects reaching the previous and current row
+        let mut open: Vec<usize> = Vec::new();
+        let mut next_open: Vec<usize> = Vec::new();
+        let mut current_row = None;
 
-                    // Skip past this span
-                    i = j;
-                    continue;
+        for rect in rects {
+            if current_row != Some(rect.row) {
+                if current_row.is_some_and(|row| row + 1 == rect.row) {
+                    open = std::mem::take(&mut next_open);
+                } else {
+                    open.clear();
+                    next_open.clear();
                 }
-                i += 1;
+                current_row = Some(rect.row);
             }
 
-            // Second pass: draw vertical components and non-horizontal box chars
-            for (col_idx, cell) in cells_vec.iter() {
-                let ch = cell.c;
-
-                if ch == ' ' || ch == '\0' {
-                    continue;
+            let above = open.iter().position(|&i| {
+                let candidate = &merged[i];
+                candidate.start_col == rect.start_col
+                    && candidate.end_col == rect.end_col
+                    && candidate.color == rect.color
+            });
+            match above {
+                Some(pos) => {
+                    let i = open.swap_remove(pos);
+                    merged[i].rows += 1;
+                    next_open.push(i);
                 }
-
-                let x = origin.x + self.cell_width * (*col_idx as f32);
-                let fg_color = self.palette.resolve(cell.fg, colors);
-
-                if box_drawing::is_box_drawing_char(ch) {
-                    let cell_bounds = Bounds {
-                        origin: Point { x, y: y_base },
-                        size: Size {
-                            width: self.cell_width,
-                            height: self.cell_height,
-                        },
-                    };
-
-                    if processed_horizontal.contains(col_idx) {
-                        // Horizontal already drawn, just draw vertical components
-                        box_drawing::draw_vertical_components(
-                            ch,
-                            cell_bounds,
-                            fg_color,
-                            self.cell_width,
-                            window,
-                        );
-                    } else {
-                        // Not part of a horizontal span, draw the whole character
-                        box_drawing::draw_box_character(
-                            ch,
-                            cell_bounds,
-                            fg_color,
-                            self.cell_width,
-                            window,
-                        );
-                    }
-                    continue;
+                None => {
+                    next_open.push(merged.len());
+                    merged.push(rect);
                 }
             }
+        }
 
-            // Third pass: draw regular text characters
-            for (col_idx, cell) in cells_vec.iter() {
-                let ch = cell.c;
+        merged
+    }
 
-                // Skip empty cells and box-drawing (already handled)
-                if ch == ' ' || ch == '\0' || box_drawing::is_box_drawing_char(ch) {
-                    continue;
-                }
+    /// Check whether the configured font family provides a real italic face.
+    ///
+    /// The text system falls back to the upright face when no italic variant
+    /// exists, so both styles resolving to the same font indicates a missing face.
+    fn has_italic_face(&self, window: &Window) -> bool {
+        let upright = Font {
+            family: self.font_family.clone().into(),
+            features: FontFeatures::default(),
+            fallbacks: None,
+            weight: FontWeight::NORMAL,
+            style: FontStyle::Normal,
+        };
+        let italic = Font {
+            style: FontStyle::Italic,
+            ..upright.clone()
+        };
 
-                let x = origin.x + self.cell_width * (*col_idx as f32);
-                let fg_color = self.palette.resolve(cell.fg, colors);
+        let text_system = window.text_system();
+        text_system.resolve_font(&upright) != text_system.resolve_font(&italic)
+    }
 
-                // For regular text, apply vertical offset for centering
-                let y = y_base + vertical_offset;
-
-                // Get cell flags for styling
-                let flags = cell.flags;
-                let bold = flags.contains(alacritty_terminal::term::cell::Flags::BOLD);
-                let italic = flags.contains(alacritty_terminal::term::cell::Flags::ITALIC);
-                let underline = flags.contains(alacritty_terminal::term::cell::Flags::UNDERLINE);
-
-                // Create font with styling
-                let font = Font {
-                    family: self.font_family.clone().into(),
-                    features: FontFeatures::default(),
-                    fallbacks: None,
-                    weight: if bold {
-                        FontWeight::BOLD
-                    } else {
-                        FontWeight::NORMAL
+    /// Paint a shaped glyph with a synthetic italic slant.
+    ///
+    /// GPUI doesn't expose glyph transforms, so the glyph is painted once per
+    /// horizontal band of the cell, clipped to that band and shifted right in
+    /// proportion to its height above the baseline.
+    fn paint_synthetic_italic(
+        &self,
+        shaped_line: &ShapedLine,
+        origin: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let slice_height = self.cell_height / ITALIC_SLICES as f32;
+        let padding_top = (self.cell_height - shaped_line.ascent - shaped_line.descent) / 2.0;
+        let baseline = origin.y + padding_top + shaped_line.ascent;
+
+        for slice in 0..ITALIC_SLICES {
+            let slice_top = origin.y + slice_height * slice as f32;
+            let slice_center = slice_top + slice_height / 2.0;
+            let shift = (baseline - slice_center) * ITALIC_SKEW;
+
+            let mask = ContentMask {
+                bounds: Bounds {
+                    origin: Point {
+                        x: origin.x - self.cell_width,
+                        y: slice_top,
                     },
-                    style: if italic {
-                        FontStyle::Italic
-                    } else {
-                        FontStyle::Normal
+                    size: Size {
+                        width: self.cell_width * 3.0,
+                        height: slice_height,
                     },
-                };
+                },
+            };
 
-                // Create text run for this single character
-                let char_str = ch.to_string();
-                let text_run = TextRun {
-                    len: char_str.len(),
-                    font,
-                    color: fg_color,
-                    background_color: None,
-                    underline: if underline {
-                        Some(UnderlineStyle {
-                            thickness: px(1.0),
-                            color: Some(fg_color),
-                            wavy: false,
-                        })
-                    } else {
-                        None
+            window.with_content_mask(Some(mask), |window| {
+                let _ = shaped_line.paint(
+                    Point {
+                        x: origin.x + shift,
+                        y: origin.y,
                     },
-                    strikethrough: None,
-                };
-
-                // Shape and paint the character
-                let text: SharedString = char_str.into();
-                let shaped_line =
-                    window
-                        .text_system()
-                        .shape_line(text, self.font_size, &[text_run], None);
-
-                // Paint at exact cell position (ignore errors)
-                let _ = shaped_line.paint(Point { x, y }, self.cell_height, window, _cx);
-            }
+                    self.cell_height,
+                    window,
+                    cx,
+                );
+            });
         }
+    }
 
-        // Paint cursor
-        let cursor_point = grid.cursor.point;
-        let cursor_x = origin.x + self.cell_width * (cursor_point.column.0 as f32);
-        let cursor_y = origin.y + self.cell_height * (cursor_point.line.0 as f32);
+    /// Paint the selection highlight for one visible row.
+    fn paint_selection(
+        &self,
+        line: Line,
+        row: usize,
+        num_cols: usize,
+        origin: Point<Pixels>,
+        window: &mut Window,
+    ) {
+        let Some((start_col, end_col)) = self
+            .selection
+            .as_ref()
+            .and_then(|selection| selection.columns_on_line(line, num_cols))
+        else {
+            return;
+        };
 
-        let cursor_color = self.palette.resolve(
-            Color::Named(alacritty_terminal::vte::ansi::NamedColor::Cursor),
-            colors,
-        );
+        let mut color = self.palette.foreground();
+        color.a = 0.3;
 
-        let cursor_bounds = Bounds {
-            origin: Point {
-                x: cursor_x,
-                y: cursor_y,
-            },
-            size: Size {
-                width: self.cell_width,
-                height: self.cell_height,
-            },
-        };
+        let y = origin.y + self.cell_height * (row as f32);
+        let rect_bounds = self.cell_bounds(origin.x, start_col, end_col - start_col, y);
 
         window.paint_quad(quad(
-            cursor_bounds,
+            rect_bounds,
             px(0.0),
-            cursor_color,
+            color,
             Edges::<Pixels>::default(),
             transparent_black(),
             Default::default(),
         ));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Paint faint 1px lines along every cell boundary, for debugging metrics.
+    ///
+    /// The grid is drawn as one quad per row and column boundary rather than
+    /// one outline per cell, so it stays cheap on large grids.
+    fn paint_cell_grid(
+        &self,
+        origin: Point<Pixels>,
+        num_cols: usize,
+        num_lines: usize,
+        window: &mut Window,
+    ) {
+        let mut color = self.palette.foreground();
+        color.a = 0.15;
 
-    #[test]
-    fn test_renderer_creation() {
-        let renderer = TerminalRenderer::new(
-            "Fira Code".to_string(),
-            px(14.0),
-            1.0,
-            ColorPalette::default(),
-        );
-        assert_eq!(renderer.font_family, "Fira Code");
-        assert_eq!(renderer.font_size, px(14.0));
-        assert_eq!(renderer.line_height_multiplier, 1.0);
+        let width = self.column_x(origin.x, num_cols) - origin.x;
+        let height = self.cell_height * (num_lines as f32);
+        let vertical = (0..=num_cols).map(|col| Bounds {
+            origin: Point {
+                x: self.column_x(origin.x, col),
+                y: origin.y,
+            },
+            size: Size {
+                width: px(1.0),
+                height,
+            },
+        });
+        let horizontal = (0..=num_lines).map(|row| Bounds {
+            origin: Point {
+                x: origin.x,
+                y: origin.y + self.cell_height * (row as f32),
+            },
+            size: Size {
+                width,
+                height: px(1.0),
+            },
+        });
+
+        for line_bounds in vertical.chain(horizontal) {
+            window.paint_quad(quad(
+                line_bounds,
+                px(0.0),
+                color,
+                Edges::<Pixels>::default(),
+                transparent_black(),
+                Default::default(),
+            ));
+        }
     }
 
-    #[test]
-    fn test_background_rect_merge() {
-        let black = Hsla::black();
-
-        let rect1 = BackgroundRect {
-            start_col: 0,
-            end_col: 5,
-            row: 0,
-            color: black,
-        };
+    /// Paint a faint 1px vertical line at the left edge of each guide column.
+    ///
+    /// A guide at `num_cols` marks the right edge of the grid; guides past it
+    /// are skipped.
+    fn paint_column_guides(
+        &self,
+        origin: Point<Pixels>,
+        num_cols: usize,
+        num_lines: usize,
+        window: &mut Window,
+    ) {
+        let mut color = self.palette.foreground();
+        color.a = 0.15;
+
+        for &col in self.column_guides.iter().filter(|&&col| col <= num_cols) {
+            let guide_bounds = Bounds {
+                origin: Point {
+                    x: self.column_x(origin.x, col),
+                    y: origin.y,
+                },
+                size: Size {
+                    width: px(1.0),
+                    height: self.cell_height * (num_lines as f32),
+                },
+            };
+            window.paint_quad(quad(
+                guide_bounds,
+                px(0.0),
+                color,
+                Edges::<Pixels>::default(),
+                transparent_black(),
+                Default::default(),
+            ));
+        }
+    }
 
-        let rect2 = BackgroundRect {
-            start_col: 5,
-            end_col: 10,
-            row: 0,
+    /// Paint search match highlights for a single visible row.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The grid line being painted (accounts for the display offset)
+    /// * `row` - The row index within the viewport
+    /// * `num_cols` - The number of columns in the grid
+    /// * `origin` - The top-left origin of the terminal content
+    /// * `window` - The GPUI window
+    fn paint_search_highlights(
+        &self,
+        line: Line,
+        row: usize,
+        num_cols: usize,
+        origin: Point<Pixels>,
+        window: &mut Window,
+    ) {
+        let mut match_color = self.palette.foreground();
+        match_color.a = 0.25;
+        let mut active_color = self.palette.foreground();
+        active_color.a = 0.5;
+
+        let matches = self
+            .search_matches
+            .iter()
+            .map(|m| (m, match_color))
+            .chain(self.active_match.iter().map(|m| (m, active_color)));
+
+        for (search_match, color) in matches {
+            let Some((start_col, end_col)) = match_columns_on_line(search_match, line, num_cols)
+            else {
+                continue;
+            };
+
+            let y = origin.y + self.cell_height * (row as f32);
+            let rect_bounds = self.cell_bounds(origin.x, start_col, end_col - start_col, y);
+
+            window.paint_quad(quad(
+                rect_bounds,
+                px(0.0),
+                color,
+                Edges::<Pixels>::default(),
+                transparent_black(),
+                Default::default(),
+            ));
+        }
+    }
+
+    /// Let the custom glyph renderer draw a character, if one is set.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the character was drawn and default rendering should be skipped.
+    fn paint_custom_glyph(
+        &self,
+        ch: char,
+        bounds: Bounds<Pixels>,
+        color: Hsla,
+        window: &mut Window,
+    ) -> bool {
+        self.glyph_renderer
+            .as_ref()
+            .is_some_and(|renderer| renderer(ch, bounds, color, window))
+    }
+
+    /// Paint the `[process exited]` label over the bottom-right of the grid.
+    ///
+    /// The label sits on the last row inside the padding, on the default
+    /// background so it stays readable over output, in a faded foreground.
+    fn paint_exit_indicator(
+        &self,
+        bounds: Bounds<Pixels>,
+        padding: Edges<Pixels>,
+        term: &Term<GpuiEventProxy>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        const LABEL: &str = "[process exited]";
+
+        let colors = term.colors();
+        let background = self.palette.resolve(
+            Color::Named(alacritty_terminal::vte::ansi::NamedColor::Background),
+            colors,
+        );
+        let mut foreground = self.palette.resolve(
+            Color::Named(alacritty_terminal::vte::ansi::NamedColor::Foreground),
+            colors,
+        );
+        foreground.a *= 0.6;
+
+        let text_run = TextRun {
+            len: LABEL.len(),
+            font: Font {
+                family: self.font_family.clone().into(),
+                features: FontFeatures::default(),
+                fallbacks: None,
+                weight: FontWeight::NORMAL,
+                style: FontStyle::Italic,
+            },
+            color: foreground,
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+        };
+        let shaped_line =
+            window
+                .text_system()
+                .shape_line(LABEL.into(), self.font_size, &[text_run], None);
+
+        let width = shaped_line.width + self.cell_width * 2.0;
+        let label_bounds = Bounds {
+            origin: Point {
+                x: bounds.origin.x + bounds.size.width - padding.right - width,
+                y: bounds.origin.y + bounds.size.height - padding.bottom - self.cell_height,
+            },
+            size: Size {
+                width,
+                height: self.cell_height,
+            },
+        };
+        window.paint_quad(quad(
+            label_bounds,
+            px(0.0),
+            background,
+            Edges::<Pixels>::default(),
+            transparent_black(),
+            Default::default(),
+        ));
+
+        let base_height = self.cell_height / self.line_height_multiplier;
+        let origin = Point {
+            x: label_bounds.origin.x + self.cell_width,
+            y: label_bounds.origin.y + (self.cell_height - base_height) / 2.0,
+        };
+        let _ = shaped_line.paint(origin, self.cell_height, window, cx);
+    }
+
+    /// Shape and paint a single cell's character with the given color.
+    ///
+    /// Honors the cell's bold, italic and underline flags. `bounds` are the
+    /// cells the character covers; the text is centered vertically in them.
+    fn paint_cell_text(
+        &self,
+        cell: &Cell,
+        fg_color: Hsla,
+        bounds: Bounds<Pixels>,
+        synthesize_italic: bool,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        // The line height multiplier adds extra height, split evenly top/bottom
+        let base_height = self.cell_height / self.line_height_multiplier;
+        let origin = Point {
+            x: bounds.origin.x,
+            y: bounds.origin.y + (self.cell_height - base_height) / 2.0,
+        };
+
+        // Get cell flags for styling
+        let flags = cell.flags;
+        let bold = flags.contains(alacritty_terminal::term::cell::Flags::BOLD);
+        let italic = flags.contains(alacritty_terminal::term::cell::Flags::ITALIC);
+        let underline = flags.contains(alacritty_terminal::term::cell::Flags::UNDERLINE);
+
+        // Create font with styling
+        let font = Font {
+            family: self.font_family.clone().into(),
+            features: FontFeatures::default(),
+            fallbacks: None,
+            weight: if bold {
+                FontWeight::BOLD
+            } else {
+                FontWeight::NORMAL
+            },
+            style: if italic {
+                FontStyle::Italic
+            } else {
+                FontStyle::Normal
+            },
+        };
+
+        // Create text run for this cell's character and combining marks
+        let char_str = cell_text(cell);
+        let text_run = TextRun {
+            len: char_str.len(),
+            font,
+            color: fg_color,
+            background_color: None,
+            underline: if underline {
+                Some(UnderlineStyle {
+                    thickness: self.underline_thickness(),
+                    color: Some(fg_color),
+                    wavy: false,
+                })
+            } else {
+                None
+            },
+            strikethrough: None,
+        };
+
+        // Shape and paint the character
+        let text: SharedString = char_str.into();
+        let shaped_line = window
+            .text_system()
+            .shape_line(text, self.font_size, &[text_run], None);
+
+        // Paint at exact cell position (ignore errors)
+        if italic && synthesize_italic {
+            self.paint_synthetic_italic(&shaped_line, origin, window, cx);
+        } else {
+            let _ = shaped_line.paint(origin, self.cell_height, window, cx);
+        }
+
+        for line in self.underline_lines(flags, origin, bounds.size.width) {
+            window.paint_quad(quad(
+                line,
+                px(0.0),
+                fg_color,
+                Edges::default(),
+                Hsla::transparent_black(),
+                BorderStyle::default(),
+            ));
+        }
+    }
+
+    /// Underlines drawn as quads rather than by the text system, for a cell
+    /// with the given flags whose text starts at `origin` and covers `width`.
+    ///
+    /// A single underline is part of the shaped text run, so this only
+    /// returns lines for a double underline: two lines of the
+    /// [underline thickness](Self::underline_thickness), as far apart, at the
+    /// bottom of the text area and as wide as the cells.
+    fn underline_lines(
+        &self,
+        flags: Flags,
+        origin: Point<Pixels>,
+        width: Pixels,
+    ) -> Vec<Bounds<Pixels>> {
+        if !flags.contains(Flags::DOUBLE_UNDERLINE) {
+            return Vec::new();
+        }
+
+        let text_bottom = origin.y + self.cell_height / self.line_height_multiplier;
+        let thickness = self.underline_thickness();
+        [thickness * 3.0, thickness]
+            .into_iter()
+            .map(|offset| Bounds {
+                origin: Point {
+                    x: origin.x,
+                    y: text_bottom - offset,
+                },
+                size: Size {
+                    width,
+                    height: thickness,
+                },
+            })
+            .collect()
+    }
+
+    /// Thickness of underlines, scaled with the font size.
+    ///
+    /// gpui doesn't expose the font's underline metrics, so this is one pixel
+    /// per [`UNDERLINE_THICKNESS_SIZE`] of font size, rounded to whole pixels
+    /// so the lines stay crisp, and at least 1px.
+    fn underline_thickness(&self) -> Pixels {
+        let thickness = f32::from(self.font_size) / UNDERLINE_THICKNESS_SIZE;
+        px(thickness.round().max(1.0))
+    }
+
+    /// Paint terminal content to the window.
+    ///
+    /// This is the main rendering method that draws the terminal grid,
+    /// including backgrounds, text, and cursor.
+    ///
+    /// # Arguments
+    ///
+    /// * `bounds` - The bounding box to render within
+    /// * `padding` - Padding around the terminal content
+    /// * `term` - The terminal state
+    /// * `window` - The GPUI window
+    /// * `cx` - The application context
+    pub fn paint(
+        &self,
+        bounds: Bounds<Pixels>,
+        padding: Edges<Pixels>,
+        term: &Term<GpuiEventProxy>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        self.paint_content(bounds, padding, term, window, cx);
+
+        if self.exit_indicator {
+            self.paint_exit_indicator(bounds, padding, term, window, cx);
+        }
+
+        // Dim everything, cursor included, after the content is done
+        if let Some(dim) = self.dim_overlay {
+            let mut overlay = self.forced_background.unwrap_or_else(|| {
+                self.palette.resolve(
+                    Color::Named(alacritty_terminal::vte::ansi::NamedColor::Background),
+                    term.colors(),
+                )
+            });
+            overlay.a = dim.clamp(0.0, 1.0);
+            window.paint_quad(quad(
+                bounds,
+                px(0.0),
+                overlay,
+                Edges::<Pixels>::default(),
+                transparent_black(),
+                Default::default(),
+            ));
+        }
+    }
+
+    /// Paint the grid: backgrounds, highlights, text and cursor.
+    fn paint_content(
+        &self,
+        bounds: Bounds<Pixels>,
+        padding: Edges<Pixels>,
+        term: &Term<GpuiEventProxy>,
+        window: &mut Window,
+        _cx: &mut App,
+    ) {
+        // Get terminal dimensions
+        let grid = term.grid();
+        let num_lines = grid.screen_lines();
+        let num_cols = grid.columns();
+        let display_offset = grid.display_offset();
+        let colors = term.colors();
+
+        // Calculate default background color
+        let default_bg = self.palette.resolve(
+            Color::Named(alacritty_terminal::vte::ansi::NamedColor::Background),
+            colors,
+        );
+
+        // Paint the canvas fill (covers full bounds including padding)
+        window.paint_quad(quad(
+            bounds,
+            px(0.0),
+            self.forced_background.unwrap_or(default_bg),
+            Edges::<Pixels>::default(),
+            transparent_black(),
+            Default::default(),
+        ));
+
+        // Calculate origin offset (content starts after padding)
+        let origin = Point {
+            x: bounds.origin.x + padding.left,
+            y: bounds.origin.y + padding.top,
+        };
+
+        // With a forced fill, the cells themselves still show the terminal background
+        if self
+            .forced_background
+            .is_some_and(|background| background != default_bg)
+        {
+            let grid_bounds = Bounds {
+                origin,
+                size: Size {
+                    width: self.column_x(origin.x, num_cols) - origin.x,
+                    height: self.cell_height * (num_lines as f32),
+                },
+            };
+            window.paint_quad(quad(
+                grid_bounds,
+                px(0.0),
+                default_bg,
+                Edges::<Pixels>::default(),
+                transparent_black(),
+                Default::default(),
+            ));
+        }
+
+        // Only slant italics ourselves when the font can't render them
+        let synthesize_italic = self.synthesize_italic && !self.has_italic_face(window);
+
+        // Collect cells for the visible lines, shifted into scrollback by the display offset
+        let rows: Vec<Vec<(usize, Cell)>> = (0..num_lines)
+            .map(|line_idx| {
+                let line = Line(line_idx as i32 - display_offset as i32);
+                (0..num_cols)
+                    .map(|col_idx| {
+                        let point = AlacPoint::new(line, Column(col_idx));
+                        (col_idx, grid[point].clone())
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Layout the rows for backgrounds, skipping the default background color
+        let backgrounds: Vec<BackgroundRect> = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(line_idx, cells)| {
+                let (backgrounds, _) = self.layout_row(line_idx, cells.iter().cloned(), colors);
+                backgrounds
+            })
+            .filter(|bg_rect| bg_rect.color != default_bg)
+            .collect();
+
+        // Paint backgrounds, merged across rows so solid blocks are one quad
+        for bg_rect in self.merge_backgrounds_vertically(backgrounds) {
+            let y = origin.y + self.cell_height * (bg_rect.row as f32);
+            let cols = bg_rect.end_col - bg_rect.start_col;
+            let mut rect_bounds = self.cell_bounds(origin.x, bg_rect.start_col, cols, y);
+            rect_bounds.size.height = self.cell_height * (bg_rect.rows as f32);
+
+            window.paint_quad(quad(
+                rect_bounds,
+                px(0.0),
+                bg_rect.color,
+                Edges::<Pixels>::default(),
+                transparent_black(),
+                Default::default(),
+            ));
+        }
+
+        self.paint_column_guides(origin, num_cols, num_lines, window);
+
+        for (line_idx, cells) in rows.iter().enumerate() {
+            let line = Line(line_idx as i32 - display_offset as i32);
+
+            // Paint search and selection highlights on top of cell backgrounds
+            self.paint_search_highlights(line, line_idx, num_cols, origin, window);
+            self.paint_selection(line, line_idx, num_cols, origin, window);
+
+            let y_base = origin.y + self.cell_height * (line_idx as f32);
+            let cy = y_base + self.cell_height / 2.0;
+
+            // Use cells vec for multiple passes (already collected above)
+            let cells_vec = cells;
+
+            // Box-drawing characters are drawn programmatically unless disabled,
+            // in which case they fall through to the text pass as font glyphs
+            if self.use_builtin_box_drawing {
+                // First pass: find and draw horizontal spans of box-drawing characters
+                // This draws continuous lines across multiple cells to avoid gaps
+                let mut processed_horizontal: std::collections::HashSet<usize> =
+                    std::collections::HashSet::new();
+
+                let mut i = 0;
+                while i < cells_vec.len() {
+                    let (col_idx, ref cell) = cells_vec[i];
+                    let ch = cell.c;
+
+                    // Check if this starts a horizontal span
+                    if let Some(weight) = box_drawing::get_horizontal_weight(ch) {
+                        let fg_color = self.palette.resolve(cell.fg, colors);
+                        let start_col = col_idx;
+                        let mut end_col = col_idx;
+
+                        // Look ahead for consecutive cells with same horizontal weight
+                        let mut j = i + 1;
+                        while j < cells_vec.len() {
+                            let (next_col, ref next_cell) = cells_vec[j];
+                            // Must be adjacent
+                            if next_col != end_col + 1 {
+                                break;
+                            }
+                            // Must have same horizontal weight and same color
+                            let next_fg = self.palette.resolve(next_cell.fg, colors);
+                            if box_drawing::get_horizontal_weight(next_cell.c) == Some(weight)
+                                && next_fg == fg_color
+                            {
+                                end_col = next_col;
+                                j += 1;
+                            } else {
+                                break;
+                            }
+                        }
+
+                        // Draw the horizontal span
+                        let span =
+                            self.cell_bounds(origin.x, start_col, end_col + 1 - start_col, y_base);
+
+                        box_drawing::draw_horizontal_span(
+                            span.left(),
+                            span.right(),
+                            cy,
+                            weight,
+                            self.cell_width,
+                            fg_color,
+                            window,
+                        );
+
+                        // Mark these columns as having horizontal drawn
+                        for col in start_col..=end_col {
+                            processed_horizontal.insert(col);
+                        }
+
+                        // Skip past this span
+                        i = j;
+                        continue;
+                    }
+                    i += 1;
+                }
+
+                // Second pass: draw vertical components and non-horizontal box chars
+                for (col_idx, cell) in cells_vec.iter() {
+                    let ch = cell.c;
+
+                    if matches!(ch, ' ' | '\0' | '\t') {
+                        continue;
+                    }
+
+                    let fg_color = self.palette.resolve(cell.fg, colors);
+
+                    if box_drawing::is_box_drawing_char(ch) {
+                        let cell_bounds = self.cell_bounds(origin.x, *col_idx, 1, y_base);
+
+                        if processed_horizontal.contains(col_idx) {
+                            // Horizontal already drawn, just draw vertical components
+                            box_drawing::draw_vertical_components(
+                                ch,
+                                cell_bounds,
+                                fg_color,
+                                self.cell_width,
+                                window,
+                            );
+                        } else {
+                            // Not part of a horizontal span, draw the whole character
+                            box_drawing::draw_box_character(
+                                ch,
+                                cell_bounds,
+                                fg_color,
+                                self.cell_width,
+                                window,
+                            );
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            // Block mosaics (quadrants and sextants) are filled as sub-cell rectangles
+            for (col_idx, cell) in cells_vec.iter() {
+                if !box_drawing::is_block_mosaic_char(cell.c) {
+                    continue;
+                }
+
+                let cell_bounds = self.cell_bounds(origin.x, *col_idx, 1, y_base);
+                let fg_color = self.palette.resolve(cell.fg, colors);
+                box_drawing::draw_block_mosaic(cell.c, cell_bounds, fg_color, window);
+            }
+
+            // Whitespace markers, in a faint shade of the cell background
+            if self.show_whitespace {
+                for (col_idx, marker) in whitespace_markers(cells_vec) {
+                    let bg = self.palette.resolve(cells_vec[col_idx].1.bg, colors);
+                    let marker_cell = Cell {
+                        c: marker,
+                        ..Cell::default()
+                    };
+                    self.paint_cell_text(
+                        &marker_cell,
+                        whitespace_color(bg),
+                        self.cell_bounds(origin.x, col_idx, 1, y_base),
+                        false,
+                        window,
+                        _cx,
+                    );
+                }
+            }
+
+            // Third pass: draw regular text characters
+            for (col_idx, cell) in cells_vec.iter() {
+                let ch = cell.c;
+
+                // Skip empty cells, box-drawing and block mosaics (already handled)
+                if (matches!(ch, ' ' | '\0' | '\t') && cell.zerowidth().is_none())
+                    || (self.use_builtin_box_drawing && box_drawing::is_box_drawing_char(ch))
+                    || box_drawing::is_block_mosaic_char(ch)
+                {
+                    continue;
+                }
+
+                let fg_color = self.palette.resolve(cell.fg, colors);
+
+                // Give a custom glyph renderer the first chance to draw the cell
+                let cell_bounds = self.cell_bounds(origin.x, *col_idx, 1, y_base);
+                if self.paint_custom_glyph(ch, cell_bounds, fg_color, window) {
+                    continue;
+                }
+
+                // Wide characters cover their spacer cell too
+                let cols = if cell.flags.contains(Flags::WIDE_CHAR) {
+                    2
+                } else {
+                    1
+                };
+                self.paint_cell_text(
+                    cell,
+                    fg_color,
+                    self.cell_bounds(origin.x, *col_idx, cols, y_base),
+                    synthesize_italic,
+                    window,
+                    _cx,
+                );
+            }
+        }
+
+        if self.draw_cell_grid {
+            self.paint_cell_grid(origin, num_cols, num_lines, window);
+        }
+
+        // The selection cursor shows regardless of the program's cursor
+        if let Some(point) = self
+            .selection_cursor
+            .and_then(|point| point_to_viewport(display_offset, point))
+        {
+            let y = origin.y + self.cell_height * (point.line as f32);
+            let bounds = self.cell_bounds(origin.x, point.column.0, 1, y);
+            let color = self.palette.resolve(
+                Color::Named(alacritty_terminal::vte::ansi::NamedColor::Cursor),
+                colors,
+            );
+            window.paint_quad(quad(
+                bounds,
+                px(0.0),
+                transparent_black(),
+                Edges::all(px(1.0)),
+                color,
+                BorderStyle::Solid,
+            ));
+        }
+
+        // Read from the same locked snapshot as the cells above, so the cursor
+        // can never lag behind the text during fast output
+        let (cursor_grid_point, cursor_cols) = cursor_cells(term);
+        let cursor_cell = &grid[cursor_grid_point];
+
+        // Skip the cursor when the program hid it (DECTCEM) or during the
+        // hidden phase of its blink
+        if !term.mode().contains(TermMode::SHOW_CURSOR) || !self.cursor_visible {
+            return;
+        }
+
+        // Paint cursor (only when it is inside the visible viewport)
+        let Some(cursor_point) = point_to_viewport(display_offset, cursor_grid_point) else {
+            return;
+        };
+        let cursor_y = origin.y + self.cell_height * (cursor_point.line as f32);
+        let shape = term.cursor_style().shape;
+
+        let cursor_color = if self.cursor_follows_fg {
+            self.palette.resolve(cursor_cell.fg, colors)
+        } else {
+            self.palette.resolve(
+                Color::Named(alacritty_terminal::vte::ansi::NamedColor::Cursor),
+                colors,
+            )
+        };
+
+        let cursor_bounds =
+            self.cell_bounds(origin.x, cursor_point.column.0, cursor_cols, cursor_y);
+
+        match self.cursor_state {
+            CursorState::Focused => window.paint_quad(quad(
+                self.cursor_bounds(origin, cursor_point, cursor_cols, shape),
+                px(0.0),
+                cursor_color,
+                Edges::<Pixels>::default(),
+                transparent_black(),
+                Default::default(),
+            )),
+            CursorState::Recent => {
+                window.paint_quad(quad(
+                    cursor_bounds,
+                    px(0.0),
+                    transparent_black(),
+                    Edges::all(px(1.0)),
+                    cursor_color,
+                    BorderStyle::Dashed,
+                ));
+                return;
+            }
+            CursorState::Idle => {
+                let mut dimmed = cursor_color;
+                dimmed.a *= 0.35;
+                window.paint_quad(quad(
+                    cursor_bounds,
+                    px(0.0),
+                    dimmed,
+                    Edges::<Pixels>::default(),
+                    transparent_black(),
+                    Default::default(),
+                ));
+                return;
+            }
+        }
+
+        // Redraw the character under the block in the cell's background color
+        // so it stays readable through the cursor
+        if self.cursor_inverts_text && shape != CursorShape::Beam {
+            let cell = cursor_cell;
+            let ch = cell.c;
+            if ch != ' '
+                && ch != '\0'
+                && ch != '\t'
+                && !(self.use_builtin_box_drawing && box_drawing::is_box_drawing_char(ch))
+                && !box_drawing::is_block_mosaic_char(ch)
+            {
+                let text_color = self.palette.resolve(cell.bg, colors);
+                if self.paint_custom_glyph(ch, cursor_bounds, text_color, window) {
+                    return;
+                }
+                self.paint_cell_text(
+                    cell,
+                    text_color,
+                    cursor_bounds,
+                    synthesize_italic,
+                    window,
+                    _cx,
+                );
+            }
+        }
+    }
+
+    /// Compute the area the focused cursor fills.
+    ///
+    /// `point` is the cursor's first cell on screen and `cols` the number of
+    /// cells it covers (two on a wide character). A bar is
+    /// [`bar_cursor_width`](Self::bar_cursor_width) wide at the left edge of
+    /// the first cell, so on a wide character it sits before the glyph rather
+    /// than inside it; every other shape fills the cells.
+    fn cursor_bounds(
+        &self,
+        origin: Point<Pixels>,
+        point: AlacPoint<usize>,
+        cols: usize,
+        shape: CursorShape,
+    ) -> Bounds<Pixels> {
+        let x = self.column_x(origin.x, point.column.0);
+        let cells_width = self.column_x(origin.x, point.column.0 + cols) - x;
+        let width = if shape == CursorShape::Beam {
+            self.bar_cursor_width.min(cells_width)
+        } else {
+            cells_width
+        };
+
+        Bounds {
+            origin: Point {
+                x,
+                y: origin.y + self.cell_height * (point.line as f32),
+            },
+            size: Size {
+                width,
+                height: self.cell_height,
+            },
+        }
+    }
+
+    /// Rasterize the visible grid into an RGBA image without a window.
+    ///
+    /// This is a low-fidelity software renderer for thumbnails and golden-image
+    /// tests: each cell is filled with its background color and every
+    /// non-blank character is drawn as a solid block in its foreground color.
+    /// Cells are scaled to fit `size`, so the image does not depend on the
+    /// measured font metrics.
+    ///
+    /// # Arguments
+    ///
+    /// * `term` - The terminal state
+    /// * `size` - The image size in pixels
+    ///
+    /// # Returns
+    ///
+    /// `size.width * size.height * 4` bytes of row-major RGBA data.
+    pub fn render_to_pixels(&self, term: &Term<GpuiEventProxy>, size: Size<u32>) -> Vec<u8> {
+        let grid = term.grid();
+        let num_lines = grid.screen_lines();
+        let num_cols = grid.columns();
+        let display_offset = grid.display_offset() as i32;
+        let colors = term.colors();
+
+        let width = size.width as usize;
+        let height = size.height as usize;
+        let mut pixels = vec![0; width * height * 4];
+        if num_lines == 0 || num_cols == 0 {
+            return pixels;
+        }
+
+        // Resolve each visible cell once, row-major: (background, optional text block color)
+        let cells: Vec<_> = (0..num_lines)
+            .flat_map(|row| (0..num_cols).map(move |col| (row, col)))
+            .map(|(row, col)| {
+                let line = Line(row as i32 - display_offset);
+                let cell = &grid[AlacPoint::new(line, Column(col))];
+                let bg = rgba_bytes(self.palette.resolve(cell.bg, colors));
+                let blank = matches!(cell.c, ' ' | '\0' | '\t')
+                    || cell.flags.contains(Flags::WIDE_CHAR_SPACER);
+                let fg = (!blank).then(|| rgba_bytes(self.palette.resolve(cell.fg, colors)));
+                (bg, fg)
+            })
+            .collect();
+
+        for y in 0..height {
+            // Position within the grid in fractional cells
+            let cell_y = (y as f32 + 0.5) * num_lines as f32 / height as f32;
+            let row = (cell_y as usize).min(num_lines - 1);
+            let in_cell_y = cell_y.fract();
+
+            for x in 0..width {
+                let cell_x = (x as f32 + 0.5) * num_cols as f32 / width as f32;
+                let col = (cell_x as usize).min(num_cols - 1);
+                let in_cell_x = cell_x.fract();
+
+                // Text occupies a block inside the cell, leaving a margin for spacing
+                let (bg, fg) = cells[row * num_cols + col];
+                let in_glyph =
+                    (0.15..0.85).contains(&in_cell_x) && (0.25..0.85).contains(&in_cell_y);
+                let color = match fg {
+                    Some(fg) if in_glyph => fg,
+                    _ => bg,
+                };
+
+                let offset = (y * width + x) * 4;
+                pixels[offset..offset + 4].copy_from_slice(&color);
+            }
+        }
+
+        pixels
+    }
+}
+
+/// Compute how many columns and rows fit in an area.
+///
+/// This is the sizing the terminal view applies on every paint, so hosts can
+/// size a window for a given grid (or the reverse) before creating a view.
+/// Partial cells don't count, and the result is at least 1x1 even when the
+/// area is smaller than a cell or the cell size is zero.
+///
+/// # Arguments
+///
+/// * `size` - The size of the whole area, padding included
+/// * `cell` - The size of one cell, e.g. from
+///   [`TerminalView::cell_size`](crate::TerminalView::cell_size)
+/// * `padding` - Space kept free inside the area
+///
+/// # Returns
+///
+/// A tuple of `(cols, rows)`.
+///
+/// # Examples
+///
+/// ```
+/// use gpui::{Edges, px, size};
+/// use gpui_terminal::render::grid_dimensions_for;
+///
+/// let area = size(px(810.0), px(490.0));
+/// let cell = size(px(8.0), px(16.0));
+/// assert_eq!(grid_dimensions_for(area, cell, Edges::all(px(5.0))), (100, 30));
+/// ```
+pub fn grid_dimensions_for(
+    size: Size<Pixels>,
+    cell: Size<Pixels>,
+    padding: Edges<Pixels>,
+) -> (usize, usize) {
+    let fit = |available: Pixels, cell: Pixels| {
+        let count = f32::from(available) / f32::from(cell);
+        if count.is_finite() && count >= 1.0 {
+            count as usize
+        } else {
+            1
+        }
+    };
+    (
+        fit(size.width - padding.left - padding.right, cell.width),
+        fit(size.height - padding.top - padding.bottom, cell.height),
+    )
+}
+
+/// Compute the left edge of a column, snapped to the device pixel grid.
+///
+/// The cell width is fractional, so `col * cell_width` drifts off whole
+/// pixels, and passes that rounded differently would leave hairline seams
+/// between backgrounds, text and box-drawing on wide grids. The renderer and
+/// the view's pixel geometry both go through this so they agree.
+pub(crate) fn column_x(
+    origin_x: Pixels,
+    cell_width: Pixels,
+    col: usize,
+    scale_factor: f32,
+) -> Pixels {
+    let x = f32::from(origin_x + cell_width * (col as f32));
+    let scale = scale_factor.max(f32::EPSILON);
+    px((x * scale).round() / scale)
+}
+
+/// Convert a color to 8-bit RGBA components.
+fn rgba_bytes(color: Hsla) -> [u8; 4] {
+    let rgba = color.to_rgb();
+    [rgba.r, rgba.g, rgba.b, rgba.a].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+/// The text a cell displays: its character (or a space if empty) followed by
+/// any zero-width combining characters attached to it.
+fn cell_text(cell: &Cell) -> String {
+    let mut text = String::new();
+    text.push(if cell.c == '\0' { ' ' } else { cell.c });
+    if let Some(zerowidth) = cell.zerowidth() {
+        text.extend(zerowidth);
+    }
+    text
+}
+
+/// Pick the whitespace markers of a row: `·` for spaces and `→` for tabs.
+///
+/// alacritty stores written spaces like cells nothing was written to, so only
+/// spaces before the last character of the row are marked. The blank cells a
+/// tab advanced over (up to the next default tab stop) belong to the tab.
+fn whitespace_markers(cells: &[(usize, Cell)]) -> Vec<(usize, char)> {
+    let end = cells
+        .iter()
+        .rposition(|(_, cell)| !is_space(cell))
+        .map_or(0, |i| i + 1);
+
+    let mut markers = Vec::new();
+    let mut tab_end = 0;
+    for &(col, ref cell) in &cells[..end] {
+        if cell.c == '\t' {
+            markers.push((col, '→'));
+            tab_end = (col / TAB_WIDTH + 1) * TAB_WIDTH;
+        } else if col >= tab_end && is_space(cell) {
+            markers.push((col, '·'));
+        }
+    }
+    markers
+}
+
+/// Whether a cell holds a space (or nothing), as opposed to a character or
+/// the spacer of a wide one.
+fn is_space(cell: &Cell) -> bool {
+    matches!(cell.c, ' ' | '\0')
+        && cell.zerowidth().is_none()
+        && !cell
+            .flags
+            .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+}
+
+/// A low-contrast shade of `bg` for whitespace markers.
+fn whitespace_color(bg: Hsla) -> Hsla {
+    let l = if bg.l > 0.5 { bg.l - 0.25 } else { bg.l + 0.25 };
+    Hsla { l, a: 1.0, ..bg }
+}
+
+/// Compute the column range a match covers on a given line.
+///
+/// # Returns
+///
+/// `Some((start_col, end_col))` with an exclusive end column, or `None` if the
+/// match does not touch the line.
+fn match_columns_on_line(
+    search_match: &Match,
+    line: Line,
+    num_cols: usize,
+) -> Option<(usize, usize)> {
+    let start = search_match.start();
+    let end = search_match.end();
+    if line < start.line || line > end.line {
+        return None;
+    }
+
+    let start_col = if line == start.line {
+        start.column.0
+    } else {
+        0
+    };
+    let end_col = if line == end.line {
+        end.column.0 + 1
+    } else {
+        num_cols
+    };
+
+    (start_col < end_col).then_some((start_col, end_col.min(num_cols)))
+}
+
+/// Locate the cells the cursor covers, as `(first cell, width in columns)`.
+///
+/// A cursor reported on the spacer half of a wide character belongs to the
+/// character's first cell, and covers both halves without crossing the line end.
+fn cursor_cells(term: &Term<GpuiEventProxy>) -> (AlacPoint, usize) {
+    let grid = term.grid();
+    let mut point = grid.cursor.point;
+    if grid[point].flags.contains(Flags::WIDE_CHAR_SPACER) && point.column.0 > 0 {
+        point.column -= 1;
+    }
+
+    let cols =
+        if grid[point].flags.contains(Flags::WIDE_CHAR) && point.column.0 + 1 < grid.columns() {
+            2
+        } else {
+            1
+        };
+    (point, cols)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renderer_creation() {
+        let renderer = TerminalRenderer::new(
+            "Fira Code".to_string(),
+            px(14.0),
+            1.0,
+            ColorPalette::default(),
+        );
+        assert_eq!(renderer.font_family, "Fira Code");
+        assert_eq!(renderer.font_size, px(14.0));
+        assert_eq!(renderer.line_height_multiplier, 1.0);
+    }
+
+    #[test]
+    fn test_background_rect_merge() {
+        let black = Hsla::black();
+
+        let rect1 = BackgroundRect {
+            start_col: 0,
+            end_col: 5,
+            row: 0,
+            rows: 1,
+            color: black,
+        };
+
+        let rect2 = BackgroundRect {
+            start_col: 5,
+            end_col: 10,
+            row: 0,
+            rows: 1,
             color: black,
         };
 
@@ -787,6 +1927,7 @@ mod tests {
             start_col: 5,
             end_col: 10,
             row: 1,
+            rows: 1,
             color: black,
         };
 
@@ -808,12 +1949,14 @@ mod tests {
                 start_col: 0,
                 end_col: 5,
                 row: 0,
+                rows: 1,
                 color: black,
             },
             BackgroundRect {
                 start_col: 5,
                 end_col: 10,
                 row: 0,
+                rows: 1,
                 color: black,
             },
         ];
@@ -823,4 +1966,360 @@ mod tests {
         assert_eq!(merged[0].start_col, 0);
         assert_eq!(merged[0].end_col, 10);
     }
+
+    #[test]
+    fn test_merge_backgrounds_vertically() {
+        let renderer = TerminalRenderer::new(
+            "monospace".to_string(),
+            px(14.0),
+            1.0,
+            ColorPalette::default(),
+        );
+        let red = Hsla::red();
+        let block = |row| BackgroundRect {
+            start_col: 3,
+            end_col: 13,
+            row,
+            rows: 1,
+            color: red,
+        };
+
+        let merged = renderer.merge_backgrounds_vertically((0..10).map(block).collect());
+        assert_eq!(merged.len(), 1);
+        assert_eq!((merged[0].start_col, merged[0].end_col), (3, 13));
+        assert_eq!((merged[0].row, merged[0].rows), (0, 10));
+
+        // A gap between rows or a different span starts a new rect
+        let mut rects = vec![block(0), block(1), block(3)];
+        rects.push(BackgroundRect {
+            end_col: 12,
+            ..block(4)
+        });
+        let merged = renderer.merge_backgrounds_vertically(rects);
+        let spans: Vec<_> = merged.iter().map(|rect| (rect.row, rect.rows)).collect();
+        assert_eq!(spans, [(0, 2), (3, 1), (4, 1)]);
+    }
+
+    #[test]
+    fn test_layout_row_keeps_combining_marks() {
+        use crate::terminal::TerminalState;
+        use std::sync::mpsc::channel;
+
+        let (tx, _rx) = channel();
+        let mut terminal = TerminalState::new(10, 2, GpuiEventProxy::new(tx));
+        terminal.process_bytes("e\u{301}x".as_bytes());
+
+        let renderer = TerminalRenderer::new(
+            "monospace".to_string(),
+            px(14.0),
+            1.0,
+            ColorPalette::default(),
+        );
+        let (_, runs) = terminal.with_term(|term| {
+            let grid = term.grid();
+            let cells = (0..grid.columns())
+                .map(|col| (col, grid[AlacPoint::new(Line(0), Column(col))].clone()));
+            renderer.layout_row(0, cells, term.colors())
+        });
+
+        assert!(runs[0].text.starts_with("e\u{301}x"));
+    }
+
+    #[test]
+    fn test_grid_dimensions_for() {
+        let cell = Size {
+            width: px(10.0),
+            height: px(20.0),
+        };
+        let area = |width: f32, height: f32| Size {
+            width: px(width),
+            height: px(height),
+        };
+
+        assert_eq!(
+            grid_dimensions_for(area(800.0, 600.0), cell, Edges::default()),
+            (80, 30)
+        );
+
+        // Partial cells are dropped, padding is subtracted on both sides
+        assert_eq!(
+            grid_dimensions_for(area(809.0, 619.0), cell, Edges::default()),
+            (80, 30)
+        );
+        assert_eq!(
+            grid_dimensions_for(area(800.0, 600.0), cell, Edges::all(px(10.0))),
+            (78, 29)
+        );
+
+        // Always at least one cell
+        assert_eq!(
+            grid_dimensions_for(area(5.0, 5.0), cell, Edges::default()),
+            (1, 1)
+        );
+        assert_eq!(
+            grid_dimensions_for(area(100.0, 100.0), cell, Edges::all(px(80.0))),
+            (1, 1)
+        );
+        assert_eq!(
+            grid_dimensions_for(area(100.0, 100.0), Size::default(), Edges::default()),
+            (1, 1)
+        );
+    }
+
+    #[test]
+    fn test_whitespace_markers() {
+        use crate::terminal::TerminalState;
+        use std::sync::mpsc::channel;
+
+        let mut terminal = TerminalState::new(20, 1, GpuiEventProxy::new(channel().0));
+        terminal.process_bytes("a b\tc 中 ".as_bytes());
+        let cells: Vec<(usize, Cell)> = terminal.with_term(|term| {
+            (0..20)
+                .map(|col| (col, term.grid()[Line(0)][Column(col)].clone()))
+                .collect()
+        });
+
+        // The tab covers columns 3-7, `c` and the space follow at 8-9 and the
+        // wide character at 10-11; the trailing space at 12 can't be told
+        // apart from the empty cells after it
+        assert_eq!(whitespace_markers(&cells), [(1, '·'), (3, '→'), (9, '·')]);
+
+        let dark = whitespace_color(Hsla {
+            h: 0.0,
+            s: 0.0,
+            l: 0.1,
+            a: 1.0,
+        });
+        assert!((dark.l - 0.35).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_double_underline() {
+        use crate::terminal::TerminalState;
+        use std::sync::mpsc::channel;
+
+        let mut terminal = TerminalState::new(10, 1, GpuiEventProxy::new(channel().0));
+        terminal.process_bytes(b"\x1b[4:2md\x1b[0;4mu\x1b[0;1;21mb");
+        let flags: Vec<Flags> = terminal.with_term(|term| {
+            (0..3)
+                .map(|col| term.grid()[Line(0)][Column(col)].flags)
+                .collect()
+        });
+
+        let renderer = TerminalRenderer::new(
+            "monospace".to_string(),
+            px(14.0),
+            1.0,
+            ColorPalette::default(),
+        );
+        let origin = Point {
+            x: px(10.0),
+            y: px(20.0),
+        };
+
+        // Two separate 1px lines inside the cell
+        assert_eq!(renderer.underline_thickness(), px(1.0));
+        let lines = renderer.underline_lines(flags[0], origin, renderer.cell_width);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].bottom() < lines[1].top());
+        for line in &lines {
+            assert_eq!(line.size.height, px(1.0));
+            assert_eq!(line.size.width, renderer.cell_width);
+            assert_eq!(line.origin.x, origin.x);
+            assert!(line.bottom() <= origin.y + renderer.cell_height);
+        }
+
+        // Large fonts get thicker lines
+        let mut large = renderer.clone();
+        large.font_size = px(32.0);
+        assert_eq!(large.underline_thickness(), px(2.0));
+        let lines = large.underline_lines(flags[0], origin, large.cell_width);
+        assert!(lines[0].bottom() < lines[1].top());
+        assert_eq!(lines[1].size.height, px(2.0));
+
+        // A single underline is drawn with the text
+        assert!(flags[1].contains(Flags::UNDERLINE));
+        assert!(
+            renderer
+                .underline_lines(flags[1], origin, renderer.cell_width)
+                .is_empty()
+        );
+
+        // SGR 21 is "bold off", not a double underline
+        assert!(!flags[2].intersects(Flags::BOLD | Flags::ALL_UNDERLINES));
+    }
+
+    #[test]
+    fn test_cursor_follows_output() {
+        use crate::terminal::TerminalState;
+        use std::sync::mpsc::channel;
+
+        let mut terminal = TerminalState::new(10, 3, GpuiEventProxy::new(channel().0));
+        let cursor = |terminal: &TerminalState| terminal.with_term(cursor_cells);
+
+        terminal.process_bytes(b"abc");
+        assert_eq!(cursor(&terminal), (AlacPoint::new(Line(0), Column(3)), 1));
+
+        // Every batch is reflected immediately, however fast they arrive
+        for col in 1..=8 {
+            terminal.process_bytes(format!("\x1b[2;{col}H").as_bytes());
+            assert_eq!(
+                cursor(&terminal),
+                (AlacPoint::new(Line(1), Column(col - 1)), 1)
+            );
+        }
+
+        terminal.process_bytes("\x1b[3;1H一\x1b[D".as_bytes());
+        assert_eq!(cursor(&terminal), (AlacPoint::new(Line(2), Column(0)), 2));
+    }
+
+    #[test]
+    fn test_bar_cursor_on_wide_char() {
+        use crate::terminal::TerminalState;
+        use gpui::point;
+        use std::sync::mpsc::channel;
+
+        let mut terminal = TerminalState::new(10, 3, GpuiEventProxy::new(channel().0));
+        let mut renderer = TerminalRenderer::new(
+            "monospace".to_string(),
+            px(14.0),
+            1.0,
+            ColorPalette::default(),
+        );
+        renderer.cell_width = px(8.0);
+        renderer.cell_height = px(16.0);
+        renderer.bar_cursor_width = px(3.0);
+        let origin = point(px(4.0), px(2.0));
+
+        // The cursor sits on the spacer half of a wide character at column 2
+        terminal.process_bytes("ab一\x1b[4G".as_bytes());
+        let (cursor, cols) = terminal.with_term(cursor_cells);
+        let cursor = point_to_viewport(0, cursor).unwrap();
+        assert_eq!((cursor.column, cols), (Column(2), 2));
+
+        let bar = renderer.cursor_bounds(origin, cursor, cols, CursorShape::Beam);
+        assert_eq!(bar.origin, point(px(20.0), px(2.0)));
+        assert_eq!(bar.size.width, px(3.0));
+
+        let block = renderer.cursor_bounds(origin, cursor, cols, CursorShape::Block);
+        assert_eq!(block.origin, bar.origin);
+        assert_eq!(block.size.width, px(16.0));
+    }
+
+    #[test]
+    fn test_columns_snap_to_device_pixels() {
+        use gpui::point;
+
+        let mut renderer = TerminalRenderer::new(
+            "monospace".to_string(),
+            px(14.0),
+            1.0,
+            ColorPalette::default(),
+        );
+        renderer.cell_width = px(7.3);
+        renderer.cell_height = px(16.0);
+        renderer.scale_factor = 2.0;
+        let origin = point(px(3.25), px(0.0));
+
+        for col in 0..300 {
+            let x = renderer.column_x(origin.x, col);
+            let next = renderer.column_x(origin.x, col + 1);
+            let device_x = f32::from(x) * 2.0;
+            assert_eq!(device_x, device_x.round(), "column {col}");
+
+            // Backgrounds, highlights, box-drawing, mosaics and custom glyphs
+            let cell = renderer.cell_bounds(origin.x, col, 1, origin.y);
+            assert_eq!((cell.left(), cell.right()), (x, next), "column {col}");
+
+            // A horizontal box-drawing span ends where its last cell does
+            let span = renderer.cell_bounds(origin.x, col, 3, origin.y);
+            let last = renderer.cell_bounds(origin.x, col + 2, 1, origin.y);
+            assert_eq!(
+                (span.left(), span.right()),
+                (x, last.right()),
+                "column {col}"
+            );
+
+            // Double underlines, on narrow and wide characters
+            let flags = Flags::DOUBLE_UNDERLINE;
+            for line in renderer.underline_lines(flags, cell.origin, cell.size.width) {
+                assert_eq!((line.left(), line.right()), (x, next), "column {col}");
+            }
+            let wide = renderer.cell_bounds(origin.x, col, 2, origin.y);
+            for line in
+                renderer.underline_lines(flags | Flags::WIDE_CHAR, wide.origin, wide.size.width)
+            {
+                let end = renderer.column_x(origin.x, col + 2);
+                assert_eq!((line.left(), line.right()), (x, end), "column {col}");
+            }
+
+            // The cursor
+            let cursor = renderer.cursor_bounds(
+                origin,
+                AlacPoint::new(0, Column(col)),
+                1,
+                CursorShape::Block,
+            );
+            assert_eq!((cursor.left(), cursor.right()), (x, next), "column {col}");
+        }
+    }
+
+    #[test]
+    fn test_render_to_pixels() {
+        use crate::terminal::TerminalState;
+        use std::sync::mpsc::channel;
+
+        let (tx, _rx) = channel();
+        let mut terminal = TerminalState::new(2, 1, GpuiEventProxy::new(tx));
+        terminal.process_bytes(b"\x1b[41m \x1b[0mX");
+
+        let palette = ColorPalette::default();
+        let renderer =
+            TerminalRenderer::new("monospace".to_string(), px(14.0), 1.0, palette.clone());
+        let size = Size {
+            width: 20,
+            height: 10,
+        };
+        let pixels = terminal.with_term(|term| renderer.render_to_pixels(term, size));
+        assert_eq!(pixels.len(), 20 * 10 * 4);
+
+        let pixel = |x: usize, y: usize| {
+            let offset = (y * 20 + x) * 4;
+            [
+                pixels[offset],
+                pixels[offset + 1],
+                pixels[offset + 2],
+                pixels[offset + 3],
+            ]
+        };
+        let color = |named| {
+            terminal
+                .with_term(|term| rgba_bytes(palette.resolve(Color::Named(named), term.colors())))
+        };
+
+        use alacritty_terminal::vte::ansi::NamedColor;
+        // Red background fills the whole first cell
+        assert_eq!(pixel(0, 0), color(NamedColor::Red));
+        assert_eq!(pixel(5, 5), color(NamedColor::Red));
+        // The "X" draws a foreground block inside a default-background cell
+        assert_eq!(pixel(15, 5), color(NamedColor::Foreground));
+        assert_eq!(pixel(10, 0), color(NamedColor::Background));
+    }
+
+    #[test]
+    fn test_match_columns_on_line() {
+        let search_match =
+            AlacPoint::new(Line(-1), Column(70))..=AlacPoint::new(Line(0), Column(4));
+
+        assert_eq!(
+            match_columns_on_line(&search_match, Line(-1), 80),
+            Some((70, 80))
+        );
+        assert_eq!(
+            match_columns_on_line(&search_match, Line(0), 80),
+            Some((0, 5))
+        );
+        assert_eq!(match_columns_on_line(&search_match, Line(1), 80), None);
+        assert_eq!(match_columns_on_line(&search_match, Line(-2), 80), None);
+    }
 }
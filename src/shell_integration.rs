@@ -0,0 +1,204 @@
+//! Shell integration marks (OSC 133).
+//!
+//! Shells configured for semantic prompts (fish, zsh and bash with the usual
+//! integration scripts, or starship) wrap each prompt and command in OSC 133
+//! sequences:
+//!
+//! | Sequence | Mark | Meaning |
+//! |----------|------|---------|
+//! | `ESC ] 133 ; A ST` | [`ShellMark::PromptStart`] | The prompt is about to be drawn |
+//! | `ESC ] 133 ; B ST` | [`ShellMark::InputStart`] | The prompt ended, the user types the command |
+//! | `ESC ] 133 ; C ST` | [`ShellMark::OutputStart`] | The command was submitted and runs |
+//! | `ESC ] 133 ; D [; status] ST` | [`ShellMark::CommandEnd`] | The command finished |
+//!
+//! `ST` is either BEL or `ESC \`. alacritty ignores these sequences, so
+//! [`TerminalState`](crate::terminal::TerminalState) scans the byte stream for
+//! them with a [`ShellMarkScanner`] before it reaches the parser, and records
+//! the cursor line each mark was emitted on.
+//!
+//! # Example
+//!
+//! ```
+//! use gpui_terminal::shell_integration::{ShellMark, ShellMarkScanner};
+//!
+//! let mut scanner = ShellMarkScanner::default();
+//! let marks: Vec<_> = b"\x1b]133;D;1\x07"
+//!     .iter()
+//!     .filter_map(|&byte| scanner.advance(byte))
+//!     .collect();
+//! assert_eq!(marks, [ShellMark::CommandEnd(Some(1))]);
+//! ```
+
+/// OSC payloads longer than this can't be OSC 133 marks and aren't buffered.
+const MAX_PAYLOAD: usize = 32;
+
+/// A semantic prompt boundary reported by the shell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellMark {
+    /// The shell is about to draw the prompt (`133;A`).
+    PromptStart,
+    /// The prompt ended and command input begins (`133;B`).
+    InputStart,
+    /// The command was submitted and its output begins (`133;C`).
+    OutputStart,
+    /// The command finished, with its exit status if the shell sent one (`133;D`).
+    CommandEnd(Option<i32>),
+}
+
+impl ShellMark {
+    /// Parse an OSC payload (the bytes between `ESC ]` and the terminator).
+    ///
+    /// Returns `None` for anything but a known OSC 133 mark. Extra parameters
+    /// such as `aid=...` are ignored.
+    pub fn parse(payload: &[u8]) -> Option<Self> {
+        let payload = std::str::from_utf8(payload).ok()?;
+        let mut params = payload.strip_prefix("133;")?.split(';');
+        match params.next()? {
+            "A" => Some(ShellMark::PromptStart),
+            "B" => Some(ShellMark::InputStart),
+            "C" => Some(ShellMark::OutputStart),
+            "D" => Some(ShellMark::CommandEnd(
+                params.next().and_then(|status| status.parse().ok()),
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Scanner state between bytes.
+#[derive(Debug, Default)]
+enum ScanState {
+    #[default]
+    Ground,
+    /// After an `ESC` outside of an OSC.
+    Escape,
+    /// Inside an OSC, collecting the payload (`None` once it grew too long).
+    Osc(Option<Vec<u8>>),
+    /// After an `ESC` inside an OSC, which may start the `ESC \` terminator.
+    OscEscape(Option<Vec<u8>>),
+}
+
+/// Incremental scanner that picks OSC 133 marks out of a byte stream.
+///
+/// Bytes are fed one at a time, so sequences split across reads are still
+/// recognized. The scanner only observes the stream; the bytes must still be
+/// passed to the terminal parser.
+#[derive(Debug, Default)]
+pub struct ShellMarkScanner {
+    state: ScanState,
+}
+
+impl ShellMarkScanner {
+    /// Feed one byte, returning a mark if it completed an OSC 133 sequence.
+    pub fn advance(&mut self, byte: u8) -> Option<ShellMark> {
+        match std::mem::take(&mut self.state) {
+            ScanState::Ground | ScanState::Escape if byte == 0x1b => {
+                self.state = ScanState::Escape;
+                None
+            }
+            ScanState::Ground => None,
+            ScanState::Escape => {
+                if byte == b']' {
+                    self.state = ScanState::Osc(Some(Vec::new()));
+                }
+                None
+            }
+            ScanState::Osc(payload) => match byte {
+                0x07 => payload.and_then(|payload| ShellMark::parse(&payload)),
+                0x1b => {
+                    self.state = ScanState::OscEscape(payload);
+                    None
+                }
+                // CAN and SUB abort the sequence
+                0x18 | 0x1a => None,
+                _ => {
+                    let payload = payload.and_then(|mut payload| {
+                        payload.push(byte);
+                        (payload.len() <= MAX_PAYLOAD).then_some(payload)
+                    });
+                    self.state = ScanState::Osc(payload);
+                    None
+                }
+            },
+            ScanState::OscEscape(payload) => {
+                if byte == b'\\' {
+                    payload.and_then(|payload| ShellMark::parse(&payload))
+                } else {
+                    // Any other escape ends the OSC and starts a new sequence
+                    self.state = ScanState::Escape;
+                    self.advance(byte)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(scanner: &mut ShellMarkScanner, bytes: &[u8]) -> Vec<ShellMark> {
+        bytes
+            .iter()
+            .filter_map(|&byte| scanner.advance(byte))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(ShellMark::parse(b"133;A"), Some(ShellMark::PromptStart));
+        assert_eq!(ShellMark::parse(b"133;B"), Some(ShellMark::InputStart));
+        assert_eq!(
+            ShellMark::parse(b"133;C;cmdline=ls"),
+            Some(ShellMark::OutputStart)
+        );
+        assert_eq!(
+            ShellMark::parse(b"133;D"),
+            Some(ShellMark::CommandEnd(None))
+        );
+        assert_eq!(
+            ShellMark::parse(b"133;D;127"),
+            Some(ShellMark::CommandEnd(Some(127)))
+        );
+        assert_eq!(
+            ShellMark::parse(b"133;A;aid=42"),
+            Some(ShellMark::PromptStart)
+        );
+        assert_eq!(ShellMark::parse(b"133;Z"), None);
+        assert_eq!(ShellMark::parse(b"0;title"), None);
+    }
+
+    #[test]
+    fn test_scan_terminators() {
+        let mut scanner = ShellMarkScanner::default();
+        let marks = scan(
+            &mut scanner,
+            b"$ \x1b]133;A\x07prompt\x1b]133;B\x1b\\ls\r\n",
+        );
+        assert_eq!(marks, [ShellMark::PromptStart, ShellMark::InputStart]);
+    }
+
+    #[test]
+    fn test_scan_split_sequence() {
+        let mut scanner = ShellMarkScanner::default();
+        assert!(scan(&mut scanner, b"out\x1b]13").is_empty());
+        assert!(scan(&mut scanner, b"3;D;0\x1b").is_empty());
+        assert_eq!(scan(&mut scanner, b"\\"), [ShellMark::CommandEnd(Some(0))]);
+    }
+
+    #[test]
+    fn test_scan_ignores_other_sequences() {
+        let mut scanner = ShellMarkScanner::default();
+        let long_title = [b"\x1b]0;".as_slice(), &[b'x'; 100], b"\x07"].concat();
+        assert!(scan(&mut scanner, &long_title).is_empty());
+        assert!(scan(&mut scanner, b"\x1b[31m133;A\x07").is_empty());
+
+        // An aborted OSC doesn't swallow the next one
+        let marks = scan(&mut scanner, b"\x1b]133;A\x18\x1b]133;C\x07");
+        assert_eq!(marks, [ShellMark::OutputStart]);
+
+        // An escape inside the OSC starts a new sequence
+        let marks = scan(&mut scanner, b"\x1b]133\x1b]133;A\x07");
+        assert_eq!(marks, [ShellMark::PromptStart]);
+    }
+}
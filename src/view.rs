@@ -19,6 +19,8 @@
 //! 4. GPUI repaints the terminal with the updated grid
 //!
 //! This approach ensures the terminal only wakes when data arrives, avoiding polling.
+//! While paused via [`TerminalView::set_paused`], step 3 waits and output stays
+//! queued in the channel.
 //!
 //! # Thread Safety
 //!
@@ -49,14 +51,36 @@
 
 use crate::colors::ColorPalette;
 use crate::event::{GpuiEventProxy, TerminalEvent};
-use crate::input::keystroke_to_bytes;
-use crate::render::TerminalRenderer;
+use crate::input::{
+    ClipboardShortcut, EnterMode, WordErase, backspace_bytes, clipboard_shortcut, enter_bytes,
+    keystroke_to_bytes, modify_other_keys_to_bytes, paste_to_bytes,
+};
+use crate::mouse::{
+    Selection, SelectionType, encode_modifiers, pixel_to_cell, scroll_report,
+    selection_type_from_clicks,
+};
+pub use crate::render::GlyphRenderer;
+use crate::render::{
+    CursorState, DEFAULT_CELL_HEIGHT_RATIO, DEFAULT_CELL_WIDTH_RATIO, TerminalRenderer, column_x,
+    grid_dimensions_for,
+};
 use crate::terminal::TerminalState;
+use alacritty_terminal::grid::{Dimensions, Scroll};
+use alacritty_terminal::index::{Column as AlacColumn, Line as AlacLine, Point as AlacPoint};
+use alacritty_terminal::term::color::Colors;
+use alacritty_terminal::term::search::Match;
+use alacritty_terminal::term::{Config as TermConfig, Osc52, TermMode};
+use alacritty_terminal::vte::ansi::{NamedColor, Rgb};
 use gpui::{Edges, *};
+use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::io::{Read, Write};
+use std::rc::Rc;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::thread;
+use std::time::{Duration, Instant};
 
 /// Configuration for terminal creation and runtime updates.
 ///
@@ -73,8 +97,46 @@ use std::thread;
 /// | `font_size` | 14px |
 /// | `scrollback` | 10000 |
 /// | `line_height_multiplier` | 1.0 |
+/// | `letter_spacing` | 0px |
 /// | `padding` | 0px all sides |
 /// | `colors` | Default palette |
+/// | `synthesize_italic` | false |
+/// | `max_fps` | Some(60) |
+/// | `use_builtin_box_drawing` | true |
+/// | `cursor_inverts_text` | true |
+/// | `forced_background` | None |
+/// | `copy_trim_trailing` | true |
+/// | `word_separators` | [`DEFAULT_WORD_SEPARATORS`] |
+/// | `cursor_idle_timeout` | 10s |
+/// | `show_control_pictures` | false |
+/// | `cursor_blink` | false |
+/// | `cursor_blink_interval` | 530ms |
+/// | `write_chunk_size` | Some(4096) |
+/// | `center_grid` | false |
+/// | `allow_clipboard_read` | false |
+/// | `scroll_multiplier` | 1.0 |
+/// | `clear_selection_on_output` | false |
+/// | `scroll_on_output` | [`ScrollOnOutput::IfAtBottom`] |
+/// | `term_config` | alacritty defaults |
+/// | `cell_width_ratio` | 0.6 |
+/// | `cell_height_ratio` | 1.4 |
+/// | `read_buffer_size` | 4096 |
+/// | `draw_cell_grid` | false |
+/// | `column_guides` | none |
+/// | `show_whitespace` | false |
+/// | `cursor_follows_fg` | false |
+/// | `bar_cursor_width` | 2px |
+/// | `enter_mode` | [`EnterMode::Cr`] |
+/// | `ctrl_backspace` | [`WordErase::CtrlW`] |
+/// | `sanitize_paste` | true |
+/// | `clipboard_shortcuts` | true |
+/// | `inactive_dim` | None |
+/// | `focus_follows_mouse` | false |
+/// | `show_exit_indicator` | false |
+/// | `ctrl_scroll_zoom` | true |
+/// | `min_font_size` | 6px |
+/// | `max_font_size` | 72px |
+/// | `audible_bell` | false (requires the `audible-bell` feature) |
 ///
 /// # Example
 ///
@@ -94,6 +156,7 @@ use std::thread;
 ///         .background(0x1a, 0x1a, 0x1a)
 ///         .foreground(0xe0, 0xe0, 0xe0)
 ///         .build(),
+///     ..Default::default()
 /// };
 /// ```
 ///
@@ -130,6 +193,10 @@ pub struct TerminalConfig {
     /// Default is 1.0 (no extra height)
     pub line_height_multiplier: f32,
 
+    /// Extra horizontal space added to every cell, for fonts that render too
+    /// tightly. Glyphs stay left-aligned in the wider cell.
+    pub letter_spacing: Pixels,
+
     /// Padding around the terminal content (top, right, bottom, left)
     /// The padding area renders with the terminal's background color
     pub padding: Edges<Pixels>,
@@ -137,6 +204,185 @@ pub struct TerminalConfig {
     /// Color palette for terminal colors (16 ANSI colors, 256 extended colors,
     /// foreground, background, and cursor colors)
     pub colors: ColorPalette,
+
+    /// Slant italic text manually when the font family has no italic face,
+    /// so emphasized text stays distinguishable (e.g. in man pages)
+    pub synthesize_italic: bool,
+
+    /// Maximum repaint rate while output is streaming in, in frames per second.
+    /// Output arriving faster than this is coalesced into a single deferred repaint.
+    /// `None` repaints after every batch of output.
+    pub max_fps: Option<u32>,
+
+    /// Draw box-drawing characters (U+2500-U+257F) programmatically for seamless
+    /// lines. Disable to use the font's own glyphs instead.
+    pub use_builtin_box_drawing: bool,
+
+    /// Redraw the character under the block cursor in the cell's background color
+    /// so it stays readable. Disable to paint the cursor as an opaque block.
+    pub cursor_inverts_text: bool,
+
+    /// Fill color for the padding and any gap around the grid. `None` uses the
+    /// terminal's default background, which escape sequences may change.
+    pub forced_background: Option<Hsla>,
+
+    /// Strip trailing spaces from each line of copied selection text. Disable to
+    /// keep fixed-width data intact.
+    pub copy_trim_trailing: bool,
+
+    /// Characters that end a word for double-click selection. Remove `/` or `.`
+    /// from the set to have paths and URLs select as a single word.
+    pub word_separators: String,
+
+    /// How long an unfocused terminal keeps a dashed "recent" cursor after the
+    /// last key press or click before dimming it to the idle style.
+    pub cursor_idle_timeout: Duration,
+
+    /// Raw diagnostic view: show incoming control bytes as control pictures
+    /// (`␀`, `␊`, `␛`, ...) instead of executing them. Shell integration
+    /// marks and unhandled-sequence reports are not collected meanwhile.
+    pub show_control_pictures: bool,
+
+    /// Blink the cursor while the terminal is focused. The cursor stays solid
+    /// while typing and resumes blinking once input has been idle for one interval.
+    pub cursor_blink: bool,
+
+    /// Time between cursor blink phases.
+    pub cursor_blink_interval: Duration,
+
+    /// Input larger than this many bytes (e.g. a big paste) is split into chunks
+    /// written from a background task, so a slow PTY can't block the UI thread.
+    /// `None` always writes synchronously.
+    pub write_chunk_size: Option<usize>,
+
+    /// Center the grid in the view by splitting the space left over from partial
+    /// cells evenly between both sides, instead of leaving it at the right and bottom.
+    pub center_grid: bool,
+
+    /// Let programs read the clipboard with an OSC 52 query. Off by default, as
+    /// any output (even a `cat`-ed file) could otherwise read the clipboard.
+    /// Answers come from [`TerminalView::with_clipboard_load_callback`].
+    pub allow_clipboard_read: bool,
+
+    /// Factor applied to scroll wheel and trackpad deltas before they are
+    /// converted to lines. Values above 1.0 scroll faster.
+    pub scroll_multiplier: f32,
+
+    /// Clear the selection whenever new output changes the grid, so it can't
+    /// highlight cells whose text has since changed. The selection is always
+    /// cleared when the program switches to or from the alternate screen.
+    pub clear_selection_on_output: bool,
+
+    /// Whether new output moves a display that is scrolled into the scrollback.
+    pub scroll_on_output: ScrollOnOutput,
+
+    /// Advanced settings for the underlying alacritty terminal, such as
+    /// `semantic_escape_chars` or `default_cursor_style`. `scrollback` and
+    /// `allow_clipboard_read` override the matching fields.
+    pub term_config: TermConfig,
+
+    /// Estimated cell width as a fraction of `font_size`. Each paint measures
+    /// the font before sizing the grid; the estimate is used when measuring
+    /// fails. Tune it for a known font to keep that fallback accurate.
+    pub cell_width_ratio: f32,
+
+    /// Estimated cell height as a fraction of `font_size`, used like
+    /// `cell_width_ratio`.
+    pub cell_height_ratio: f32,
+
+    /// Size in bytes of the buffer PTY output is read into. Larger buffers
+    /// mean fewer reads and batches for programs that print a lot, at the cost
+    /// of more output arriving (and being drawn) at once under heavy load.
+    pub read_buffer_size: usize,
+
+    /// Debugging aid: outline every cell with faint 1px lines, which makes
+    /// font-metric and box-drawing alignment problems easy to spot.
+    pub draw_cell_grid: bool,
+
+    /// Columns to mark with a faint vertical line, like an editor's rulers.
+    /// A guide at `80` is drawn between the 80th and 81st column; guides past
+    /// the right edge aren't drawn.
+    pub column_guides: Vec<usize>,
+
+    /// Show spaces as faint middots (`·`) and tabs as arrows (`→`), to spot
+    /// alignment problems in output. Spaces after the last character of a row
+    /// aren't marked, as they can't be told apart from empty cells.
+    pub show_whitespace: bool,
+
+    /// Color the cursor with the foreground color of the text under it
+    /// instead of the palette's cursor color, for themes where a fixed cursor
+    /// color clashes with colored output.
+    pub cursor_follows_fg: bool,
+
+    /// Thickness of the bar cursor programs select with `CSI 5 q`/`CSI 6 q`.
+    /// The bar is drawn at the left edge of the cursor cell, which on a wide
+    /// character is the left edge of the glyph.
+    pub bar_cursor_width: Pixels,
+
+    /// Line ending sent for Enter. Programs that enable line feed/new line
+    /// mode (`CSI 20 h`) get `\r\n` regardless.
+    pub enter_mode: EnterMode,
+
+    /// What Ctrl+Backspace sends to erase a word. Alt+Backspace always sends
+    /// ESC DEL.
+    pub ctrl_backspace: WordErase,
+
+    /// Drop control characters other than newline and tab from text given to
+    /// [`TerminalView::paste`] when the program hasn't enabled bracketed
+    /// paste, so pasted escape sequences can't act on the terminal.
+    pub sanitize_paste: bool,
+
+    /// Copy the selection on Ctrl+Shift+C and paste the clipboard on
+    /// Ctrl+Shift+V instead of sending those keys to the program.
+    pub clipboard_shortcuts: bool,
+
+    /// Dim the terminal while it is unfocused by painting its background color
+    /// over it at this opacity (0.0-1.0), so the active pane of a split layout
+    /// stands out. `None` leaves inactive terminals undimmed.
+    pub inactive_dim: Option<f32>,
+
+    /// Focus the terminal when the pointer moves over it, without a click,
+    /// for tiled layouts. Moves with a mouse button held (e.g. a selection
+    /// dragged out of another terminal) don't take focus.
+    pub focus_follows_mouse: bool,
+
+    /// Label the bottom-right corner with `[process exited]` once the
+    /// process has exited. The cursor is dimmed either way.
+    pub show_exit_indicator: bool,
+
+    /// Change the font size with Ctrl+scroll, which is also how pinch gestures
+    /// arrive from precision touchpads. While a program tracks the mouse,
+    /// Ctrl+scroll is reported to it instead.
+    pub ctrl_scroll_zoom: bool,
+
+    /// Smallest font size zooming goes down to.
+    pub min_font_size: Pixels,
+
+    /// Largest font size zooming goes up to.
+    pub max_font_size: Pixels,
+
+    /// Sound the system beep when the program rings the bell, in addition to
+    /// any bell callbacks. The beep is a BEL written to the controlling
+    /// terminal of the host process, so it is silent when the host wasn't
+    /// started from a terminal.
+    #[cfg(feature = "audible-bell")]
+    pub audible_bell: bool,
+}
+
+impl TerminalConfig {
+    /// The alacritty configuration: [`term_config`](Self::term_config) with the
+    /// settings this struct also covers applied on top.
+    fn alacritty_config(&self) -> TermConfig {
+        TermConfig {
+            scrolling_history: self.scrollback,
+            osc52: if self.allow_clipboard_read {
+                Osc52::CopyPaste
+            } else {
+                Osc52::OnlyCopy
+            },
+            ..self.term_config.clone()
+        }
+    }
 }
 
 impl Default for TerminalConfig {
@@ -148,12 +394,95 @@ impl Default for TerminalConfig {
             font_size: px(14.0),
             scrollback: 10000,
             line_height_multiplier: 1.0,
+            letter_spacing: px(0.0),
             padding: Edges::all(px(0.0)),
             colors: ColorPalette::default(),
+            synthesize_italic: false,
+            max_fps: Some(60),
+            use_builtin_box_drawing: true,
+            cursor_inverts_text: true,
+            forced_background: None,
+            copy_trim_trailing: true,
+            word_separators: DEFAULT_WORD_SEPARATORS.into(),
+            cursor_idle_timeout: Duration::from_secs(10),
+            show_control_pictures: false,
+            cursor_blink: false,
+            cursor_blink_interval: Duration::from_millis(530),
+            write_chunk_size: Some(4096),
+            center_grid: false,
+            allow_clipboard_read: false,
+            scroll_multiplier: 1.0,
+            clear_selection_on_output: false,
+            scroll_on_output: ScrollOnOutput::IfAtBottom,
+            term_config: TermConfig::default(),
+            cell_width_ratio: DEFAULT_CELL_WIDTH_RATIO,
+            cell_height_ratio: DEFAULT_CELL_HEIGHT_RATIO,
+            read_buffer_size: 4096,
+            draw_cell_grid: false,
+            column_guides: Vec::new(),
+            show_whitespace: false,
+            cursor_follows_fg: false,
+            bar_cursor_width: px(2.0),
+            enter_mode: EnterMode::Cr,
+            ctrl_backspace: WordErase::CtrlW,
+            sanitize_paste: true,
+            clipboard_shortcuts: true,
+            inactive_dim: None,
+            focus_follows_mouse: false,
+            show_exit_indicator: false,
+            ctrl_scroll_zoom: true,
+            min_font_size: px(6.0),
+            max_font_size: px(72.0),
+            #[cfg(feature = "audible-bell")]
+            audible_bell: false,
+        }
+    }
+}
+
+/// How the display follows new output, see [`TerminalConfig::scroll_on_output`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScrollOnOutput {
+    /// Jump to the bottom on any output, even while reading the scrollback.
+    Always,
+    /// Never move the display: output arriving at the bottom scrolls away
+    /// while the viewed lines stay put, as with a scroll lock.
+    Never,
+    /// Follow output while at the bottom, and stay put while scrolled up.
+    #[default]
+    IfAtBottom,
+}
+
+impl ScrollOnOutput {
+    /// The scroll to apply after output.
+    ///
+    /// # Arguments
+    ///
+    /// * `display_offset` - The display offset before the output
+    /// * `new_history` - How many lines the output pushed into the scrollback
+    ///
+    /// alacritty already keeps a scrolled-up display on the same lines, so
+    /// only jumping down and locking the bottom need a scroll.
+    fn scroll_after_output(self, display_offset: usize, new_history: usize) -> Option<Scroll> {
+        match self {
+            ScrollOnOutput::Always => Some(Scroll::Bottom),
+            ScrollOnOutput::Never if display_offset == 0 && new_history > 0 => {
+                Some(Scroll::Delta(new_history as i32))
+            }
+            ScrollOnOutput::Never | ScrollOnOutput::IfAtBottom => None,
         }
     }
 }
 
+/// Default [`TerminalConfig::word_separators`]: whitespace, quotes, brackets and
+/// common delimiters.
+pub const DEFAULT_WORD_SEPARATORS: &str = " ,│`|:\"'()[]{}<>\t";
+
+/// Reads the stdout thread may queue ahead of the view.
+///
+/// Once this many are waiting the thread stops reading, so output that isn't
+/// being processed backs up into the PTY instead of into memory.
+const READ_QUEUE_LEN: usize = 16;
+
 /// Callback type for PTY resize notifications.
 ///
 /// This callback is invoked when the terminal grid dimensions change,
@@ -188,6 +517,29 @@ impl Default for TerminalConfig {
 /// ```
 pub type ResizeCallback = Box<dyn Fn(usize, usize) + Send + Sync>;
 
+/// Callback for changes to where the grid is laid out.
+///
+/// Invoked during paint whenever the grid size or the pixel rectangle its
+/// cells occupy changes. The rectangle excludes the padding and the space
+/// left over by partial cells (including what
+/// [`center_grid`](TerminalConfig::center_grid) spreads around the grid), so
+/// adjacent UI can be placed flush against the text.
+///
+/// # Arguments
+///
+/// * `cols` - Number of columns
+/// * `rows` - Number of rows
+/// * `content_bounds` - The window-space rectangle covered by the cells
+///
+/// # Example
+///
+/// ```ignore
+/// terminal.with_layout_callback(move |cols, rows, content_bounds| {
+///     status_bar.update(|bar| bar.align_to(content_bounds.left(), content_bounds.right()));
+/// });
+/// ```
+pub type LayoutCallback = Box<dyn Fn(usize, usize, Bounds<Pixels>)>;
+
 /// Callback type for key event interception.
 ///
 /// This callback is invoked before the terminal processes a key event,
@@ -297,6 +649,34 @@ pub type TitleCallback = Box<dyn Fn(&mut Window, &mut Context<TerminalView>, &st
 /// ```
 pub type ClipboardStoreCallback = Box<dyn Fn(&mut Window, &mut Context<TerminalView>, &str)>;
 
+/// Callback for clipboard load requests.
+///
+/// This callback is invoked when a program queries the clipboard via an OSC 52
+/// escape sequence with a `?` payload (requires
+/// [`TerminalConfig::allow_clipboard_read`]). The returned text is encoded and
+/// written back to the program.
+///
+/// # Arguments
+///
+/// * `window` - The GPUI window
+/// * `cx` - The context for the TerminalView
+///
+/// # Returns
+///
+/// The clipboard text, or `None` to leave the query unanswered.
+///
+/// # Example
+///
+/// ```ignore
+/// use gpui_terminal::Clipboard;
+///
+/// terminal.with_clipboard_load_callback(|window, cx| {
+///     Clipboard::new().ok()?.paste().ok()
+/// });
+/// ```
+pub type ClipboardLoadCallback =
+    Box<dyn Fn(&mut Window, &mut Context<TerminalView>) -> Option<String>>;
+
 /// Callback for terminal exit events.
 ///
 /// This callback is invoked when the terminal process exits (e.g., shell exits,
@@ -323,6 +703,257 @@ pub type ClipboardStoreCallback = Box<dyn Fn(&mut Window, &mut Context<TerminalV
 /// ```
 pub type ExitCallback = Box<dyn Fn(&mut Window, &mut Context<TerminalView>)>;
 
+/// Callback for grid content changes.
+///
+/// This callback is invoked on the first frame after PTY output was processed,
+/// so it fires at most once per frame (see [`TerminalConfig::max_fps`]) and not
+/// for repaints caused by focus, cursor blink, or selection changes.
+///
+/// # Arguments
+///
+/// * `window` - The GPUI window
+/// * `cx` - The context for the TerminalView
+///
+/// # Example
+///
+/// ```ignore
+/// terminal.with_update_callback(|window, cx| {
+///     // Tell the remote viewer to fetch and diff the new screen contents
+///     mirror_tx.send(MirrorMessage::Changed).ok();
+/// });
+/// ```
+pub type UpdateCallback = Box<dyn Fn(&mut Window, &mut Context<TerminalView>)>;
+
+/// Callback for escape sequences the terminal ignores.
+///
+/// A debugging aid for escape-sequence compatibility work: it receives each
+/// sequence the parser dropped, as described in [`crate::diagnostics`], on
+/// the frame after the output containing it is processed.
+///
+/// # Arguments
+///
+/// * `window` - The GPUI window
+/// * `cx` - The context for the TerminalView
+/// * `sequence` - The ignored sequence, e.g. `ESC [ ? 9999 h`
+///
+/// # Example
+///
+/// ```ignore
+/// terminal.with_unhandled_sequence_callback(|window, cx, sequence| {
+///     eprintln!("unhandled: {:?}", String::from_utf8_lossy(sequence));
+/// });
+/// ```
+pub type UnhandledSequenceCallback = Box<dyn Fn(&mut Window, &mut Context<TerminalView>, &[u8])>;
+
+/// Callback for scrollback lines dropped over the scrollback limit.
+///
+/// Once the scrollback holds [`TerminalConfig::scrollback`] lines, every new
+/// line evicts the oldest one. The callback receives the evicted lines, oldest
+/// first and one per grid row, after the output that evicted them was
+/// processed, so they can be appended to a full session log on disk.
+///
+/// # Arguments
+///
+/// * `lines` - The evicted lines, with trailing blanks trimmed
+///
+/// # Example
+///
+/// ```ignore
+/// terminal.with_scrollback_evict_callback(move |lines| {
+///     for line in lines {
+///         writeln!(log_file, "{line}").ok();
+///     }
+/// });
+/// ```
+pub type ScrollbackEvictCallback = Box<dyn Fn(&[String])>;
+
+/// Callback for the cell under the mouse pointer.
+///
+/// Called from mouse moves over the terminal, only when the pointer enters a
+/// different cell, e.g. to show a "row, column" readout while debugging
+/// layouts.
+///
+/// # Arguments
+///
+/// * `point` - The hovered cell, in grid coordinates (negative lines are in
+///   the scrollback, see [`TerminalState::to_screen_point`] for the row on
+///   screen)
+///
+/// # Example
+///
+/// ```ignore
+/// terminal.with_hover_callback(|point| {
+///     status.set(format!("row {}, col {}", point.line.0, point.column.0));
+/// });
+/// ```
+pub type HoverCallback = Box<dyn Fn(AlacPoint)>;
+
+/// The transient decorations drawn over the terminal contents.
+///
+/// Returned by [`TerminalView::decorations`]; all of them are removed at once
+/// with [`TerminalView::clear_decorations`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Decorations {
+    /// A text selection is highlighted
+    pub selection: bool,
+    /// A search is active and its matches are highlighted
+    pub search: bool,
+    /// Keyboard selection mode is active and its cursor is shown
+    pub selection_mode: bool,
+}
+
+impl Decorations {
+    /// Whether no decoration is active.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A color programs can change at runtime with escape sequences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DynamicColor {
+    /// One of the 256 indexed colors (OSC 4)
+    Indexed(u8),
+    /// The default foreground (OSC 10)
+    Foreground,
+    /// The default background (OSC 11)
+    Background,
+    /// The cursor color (OSC 12)
+    Cursor,
+}
+
+/// Callback for colors changed by escape sequences.
+///
+/// Called once per changed color after the output that changed it was
+/// processed, so a host can keep its own UI in step with the program's theme.
+///
+/// # Arguments
+///
+/// * `color` - Which color changed
+/// * `rgb` - The color the program set, or `None` if it reset the color
+///   (OSC 104/110/111/112, or a full reset) to the configured palette
+///
+/// # Example
+///
+/// ```ignore
+/// terminal.with_color_change_callback(|color, rgb| {
+///     if color == DynamicColor::Background {
+///         update_tab_bar_background(rgb);
+///     }
+/// });
+/// ```
+pub type ColorChangeCallback = Box<dyn Fn(DynamicColor, Option<Rgb>)>;
+
+/// Tap on the raw bytes flowing to or from the PTY.
+///
+/// The input tap sees every write to the process, exactly as it is written
+/// (keys, pastes, mouse reports and terminal replies); the output tap sees
+/// each batch read from the PTY before it is parsed. Together they are enough
+/// to record a timed transcript of the session, e.g. in asciinema's format.
+///
+/// Taps run on the main thread without holding any of the terminal's locks.
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes written or read
+///
+/// # Example
+///
+/// ```ignore
+/// let start = Instant::now();
+/// terminal.with_output_tap(move |bytes| {
+///     recording.borrow_mut().push((start.elapsed(), bytes.to_vec()));
+/// });
+/// ```
+pub type PtyTap = Box<dyn Fn(&[u8])>;
+
+/// Filter rewriting the PTY output before it is parsed.
+///
+/// Unlike the [output tap](PtyTap), a filter changes what the terminal sees:
+/// it can pass a batch through (`Cow::Borrowed`), drop it (an empty slice) or
+/// replace it, e.g. to redact secrets or to strip a custom OSC it handles
+/// itself.
+///
+/// The filter runs on the main thread for every batch read from the PTY
+/// (up to [`TerminalConfig::read_buffer_size`] bytes), so it should be cheap;
+/// borrowing the input when nothing changes avoids a copy. Escape sequences
+/// can be split across batches, so a filter matching them needs to carry
+/// state between calls.
+///
+/// # Arguments
+///
+/// * `bytes` - A batch of output as read from the PTY
+///
+/// # Returns
+///
+/// The bytes to parse instead.
+///
+/// # Example
+///
+/// ```ignore
+/// terminal.with_output_filter(|bytes| {
+///     if bytes.windows(6).any(|window| window == b"secret") {
+///         Cow::Owned(redact(bytes))
+///     } else {
+///         Cow::Borrowed(bytes)
+///     }
+/// });
+/// ```
+pub type OutputFilter = Box<dyn Fn(&[u8]) -> Cow<'_, [u8]>>;
+
+/// Where the grid was last painted, used to map mouse positions to cells.
+#[derive(Clone, Copy, PartialEq)]
+struct GridLayout {
+    /// Top-left corner of the first cell (inside the padding)
+    origin: Point<Pixels>,
+
+    /// Number of columns and rows painted
+    cols: usize,
+    rows: usize,
+
+    /// Measured width of a cell
+    cell_width: Pixels,
+
+    /// Measured height of a cell
+    cell_height: Pixels,
+
+    /// Device pixels per logical pixel, which columns were snapped to
+    scale_factor: f32,
+}
+
+impl GridLayout {
+    /// The left edge of a column, as the renderer painted it.
+    fn column_x(&self, col: usize) -> Pixels {
+        column_x(self.origin.x, self.cell_width, col, self.scale_factor)
+    }
+
+    /// The rectangle covered by the cells.
+    fn content_bounds(&self) -> Bounds<Pixels> {
+        Bounds {
+            origin: self.origin,
+            size: size(
+                self.column_x(self.cols) - self.origin.x,
+                self.cell_height * self.rows as f32,
+            ),
+        }
+    }
+}
+
+/// View-level search state: the active query and a cursor over its matches.
+struct SearchState {
+    /// The query passed to [`TerminalView::start_search`]
+    query: String,
+
+    /// All matches for the query, ordered from oldest scrollback line to screen bottom
+    matches: Vec<Match>,
+
+    /// Index of the focused match in `matches`
+    current: Option<usize>,
+
+    /// Output arrived since `matches` was computed; refreshed on the next frame
+    stale: bool,
+}
+
 /// The main terminal view component for GPUI applications.
 ///
 /// `TerminalView` is a GPUI entity that implements the [`Render`] trait,
@@ -361,11 +992,30 @@ pub type ExitCallback = Box<dyn Fn(&mut Window, &mut Context<TerminalView>)>;
 /// Configure behavior through builder methods:
 ///
 /// - [`with_resize_callback`](Self::with_resize_callback) - PTY size changes
+/// - [`with_layout_callback`](Self::with_layout_callback) - Grid size and pixel placement
 /// - [`with_exit_callback`](Self::with_exit_callback) - Process exit
+/// - [`with_update_callback`](Self::with_update_callback) - Grid content changes
 /// - [`with_key_handler`](Self::with_key_handler) - Key event interception
+/// - [`with_glyph_renderer`](Self::with_glyph_renderer) - Custom character drawing
 /// - [`with_bell_callback`](Self::with_bell_callback) - Terminal bell
-/// - [`with_title_callback`](Self::with_title_callback) - Title changes
+/// - [`with_title_callback`](Self::with_title_callback) - Title changes, paused
+///   while a [title override](Self::set_title_override) is set
 /// - [`with_clipboard_store_callback`](Self::with_clipboard_store_callback) - Clipboard writes
+/// - [`with_clipboard_load_callback`](Self::with_clipboard_load_callback) - Clipboard queries
+/// - [`with_unhandled_sequence_callback`](Self::with_unhandled_sequence_callback) - Ignored
+///   escape sequences, for debugging
+/// - [`with_scrollback_evict_callback`](Self::with_scrollback_evict_callback) - Lines
+///   dropped from a full scrollback
+/// - [`with_input_tap`](Self::with_input_tap) and [`with_output_tap`](Self::with_output_tap) -
+///   Raw PTY traffic, for session recording
+/// - [`with_output_filter`](Self::with_output_filter) - Rewrites output before it is parsed
+/// - [`with_color_change_callback`](Self::with_color_change_callback) - Colors changed by
+///   OSC 4/10/11/12
+/// - [`with_hover_callback`](Self::with_hover_callback) - The cell under the mouse pointer
+///
+/// The exit, update, bell, title and clipboard store callbacks also have
+/// `add_*` variants (e.g. [`add_bell_callback`](Self::add_bell_callback)) that
+/// register further listeners instead of replacing the existing one.
 ///
 /// # Thread Safety
 ///
@@ -381,36 +1031,146 @@ pub struct TerminalView {
     /// Focus handle for keyboard event handling
     focus_handle: FocusHandle,
 
-    /// Writer for sending input to the terminal process
-    stdin_writer: Arc<parking_lot::Mutex<Box<dyn Write + Send>>>,
+    /// Writer for sending input to the terminal process, `None` after shutdown
+    stdin_writer: Arc<parking_lot::Mutex<Option<Box<dyn Write + Send>>>>,
 
     /// Receiver for terminal events from the event proxy
     event_rx: mpsc::Receiver<TerminalEvent>,
 
+    /// Events taken off `event_rx` while writing replies, handled on the next render
+    queued_events: VecDeque<TerminalEvent>,
+
     /// Configuration used to create this terminal
     config: TerminalConfig,
 
-    /// Async task that reads bytes and notifies the view (push-based)
-    #[allow(dead_code)]
-    _reader_task: Task<()>,
+    /// Async task that reads bytes and notifies the view (push-based),
+    /// `None` after shutdown
+    reader_task: Option<Task<()>>,
 
     /// Callback to notify the PTY about size changes
     resize_callback: Option<Arc<ResizeCallback>>,
 
+    /// Callback for changes to the grid's size and placement
+    layout_callback: Option<Rc<LayoutCallback>>,
+
     /// Optional callback to intercept key events before terminal processing
     key_handler: Option<Arc<KeyHandler>>,
 
-    /// Callback for terminal bell events
-    bell_callback: Option<BellCallback>,
+    /// Callbacks for terminal bell events, run in registration order
+    bell_callbacks: Vec<BellCallback>,
+
+    /// Callbacks for terminal title changes, run in registration order
+    title_callbacks: Vec<TitleCallback>,
+
+    /// Callbacks for clipboard store requests, run in registration order
+    clipboard_store_callbacks: Vec<ClipboardStoreCallback>,
+
+    /// Callback for clipboard load requests
+    clipboard_load_callback: Option<ClipboardLoadCallback>,
+
+    /// Callbacks for terminal exit events, run in registration order
+    exit_callbacks: Vec<ExitCallback>,
+
+    /// Callbacks for grid content changes, run in registration order
+    update_callbacks: Vec<UpdateCallback>,
+
+    /// Callback for escape sequences the terminal ignores
+    unhandled_sequence_callback: Option<UnhandledSequenceCallback>,
+
+    /// Callback for lines evicted from the scrollback
+    scrollback_evict_callback: Option<ScrollbackEvictCallback>,
+
+    /// Callback for colors changed by escape sequences
+    color_change_callback: Option<ColorChangeCallback>,
+
+    /// Callback for the cell under the mouse pointer
+    hover_callback: Option<HoverCallback>,
+
+    /// The cell last reported to the hover callback
+    hovered_cell: Option<AlacPoint>,
+
+    /// Tap on the bytes written to the PTY
+    input_tap: Option<PtyTap>,
+
+    /// Tap on the bytes read from the PTY
+    output_tap: Option<PtyTap>,
+
+    /// Filter applied to the bytes read from the PTY before parsing
+    output_filter: Option<OutputFilter>,
+
+    /// Whether output changed the grid since the update callback last ran
+    content_changed: bool,
+
+    /// Whether the bell rang while unfocused and hasn't been acknowledged
+    bell_pending: bool,
+
+    /// Whether the process exited (the PTY output reached EOF)
+    exited: bool,
+
+    /// The title the program last set (empty until it sets one)
+    title: String,
+
+    /// Host-provided title shown instead of the program's
+    title_override: Option<String>,
+
+    /// Whether title callbacks should be told the program's title once the
+    /// override was lifted
+    title_resumed: bool,
+
+    /// Active scrollback search, if any
+    search: Option<SearchState>,
+
+    /// When output last triggered a repaint (used for frame rate throttling)
+    last_output_notify: Option<Instant>,
+
+    /// Whether a throttled repaint is already scheduled
+    deferred_notify: bool,
+
+    /// Grid placement from the last paint, shared with the canvas callback
+    grid_layout: Arc<parking_lot::Mutex<Option<GridLayout>>>,
+
+    /// Whether the left button is held and dragging extends the selection
+    selecting: bool,
+
+    /// The word a double-click selection started on, kept while dragging
+    word_anchor: Option<(AlacPoint, AlacPoint)>,
+
+    /// The cell a single click landed on; dragging out of it starts the selection
+    press_point: Option<AlacPoint>,
+
+    /// When the user last typed or clicked in this terminal
+    last_activity: Instant,
+
+    /// Pending repaint that dims the cursor once the idle timeout passes
+    cursor_idle_task: Option<Task<()>>,
 
-    /// Callback for terminal title changes
-    title_callback: Option<TitleCallback>,
+    /// Timer toggling the cursor blink phase, running while blinking is enabled
+    cursor_blink_task: Option<Task<()>>,
 
-    /// Callback for clipboard store requests
-    clipboard_store_callback: Option<ClipboardStoreCallback>,
+    /// Chunks of large input still waiting to be written by the background writer
+    pending_input: Arc<parking_lot::Mutex<VecDeque<Vec<u8>>>>,
 
-    /// Callback for terminal exit events
-    exit_callback: Option<ExitCallback>,
+    /// Set by [`shutdown`](TerminalView::shutdown), so input can be dropped
+    /// without waiting on the writer lock
+    input_closed: Arc<AtomicBool>,
+
+    /// Whether PTY output is held back instead of being processed
+    paused: bool,
+
+    /// Wakes the reader task when output is resumed
+    resume_tx: flume::Sender<()>,
+
+    /// Scroll distance that hasn't added up to a whole line yet
+    scroll_remainder: Pixels,
+
+    /// Ctrl+scroll distance that hasn't added up to a font size step yet
+    zoom_remainder: Pixels,
+
+    /// Ends a synchronized update the program didn't finish in time
+    sync_timeout_task: Option<Task<()>>,
+
+    /// Number of output batches processed, used to detect settled output
+    output_batches: u64,
 }
 
 impl TerminalView {
@@ -459,80 +1219,156 @@ impl TerminalView {
         let event_proxy = GpuiEventProxy::new(event_tx);
 
         // Create terminal state
-        let state = TerminalState::new(config.cols, config.rows, event_proxy);
+        let mut state = TerminalState::with_config(
+            config.cols,
+            config.rows,
+            event_proxy,
+            config.alacritty_config(),
+        );
+        state.set_show_control_pictures(config.show_control_pictures);
 
         // Create renderer with font settings and color palette
-        let renderer = TerminalRenderer::new(
+        let mut renderer = TerminalRenderer::new(
             config.font_family.clone(),
             config.font_size,
             config.line_height_multiplier,
             config.colors.clone(),
         );
+        renderer.letter_spacing = config.letter_spacing;
+        renderer.cell_width = config.font_size * config.cell_width_ratio + config.letter_spacing;
+        renderer.cell_height = config.font_size * config.cell_height_ratio;
+        renderer.synthesize_italic = config.synthesize_italic;
+        renderer.use_builtin_box_drawing = config.use_builtin_box_drawing;
+        renderer.cursor_inverts_text = config.cursor_inverts_text;
+        renderer.forced_background = config.forced_background;
+        renderer.draw_cell_grid = config.draw_cell_grid;
+        renderer.column_guides = config.column_guides.clone();
+        renderer.show_whitespace = config.show_whitespace;
+        renderer.cursor_follows_fg = config.cursor_follows_fg;
+        renderer.bar_cursor_width = config.bar_cursor_width;
 
         // Create focus handle
         let focus_handle = cx.focus_handle();
 
         // Wrap stdin writer in Arc<Mutex> for thread-safe access
-        let stdin_writer = Arc::new(parking_lot::Mutex::new(
+        let stdin_writer = Arc::new(parking_lot::Mutex::new(Some(
             Box::new(stdin_writer) as Box<dyn Write + Send>
-        ));
+        )));
 
         // Create async channel for bytes (push-based notification)
         // Using flume instead of smol::channel because flume is executor-agnostic
         // and properly wakes GPUI's async executor when data arrives
-        let (bytes_tx, bytes_rx) = flume::unbounded::<Vec<u8>>();
+        let (bytes_tx, bytes_rx) = flume::bounded::<Vec<u8>>(READ_QUEUE_LEN);
 
         // Spawn background thread to read from stdout
         // This thread sends bytes through the async channel
+        let read_buffer_size = config.read_buffer_size;
         thread::spawn(move || {
-            Self::read_stdout_blocking(stdout_reader, bytes_tx);
+            Self::read_stdout_blocking(stdout_reader, bytes_tx, read_buffer_size);
         });
 
+        // Wakes the reader task when output processing is resumed
+        let (resume_tx, resume_rx) = flume::unbounded::<()>();
+
         // Spawn async task that awaits on the channel and notifies the view
         // This is push-based: the task blocks until bytes arrive, then immediately notifies
         let reader_task = cx.spawn(async move |this: WeakEntity<Self>, cx: &mut AsyncApp| {
+            // A batch received while paused, processed once output resumes
+            let mut held: Option<Vec<u8>> = None;
             loop {
-                // Wait for bytes from the background reader (blocks until data arrives)
-                match bytes_rx.recv_async().await {
-                    Ok(bytes) => {
-                        // Process bytes and notify the view
-                        let result = this.update(cx, |view: &mut Self, cx: &mut Context<Self>| {
-                            view.state.process_bytes(&bytes);
-                            cx.notify();
-                        });
-                        if result.is_err() {
-                            // View was dropped, exit
+                let bytes = match held.take() {
+                    Some(bytes) => bytes,
+                    // Wait for bytes from the background reader (blocks until data arrives)
+                    None => match bytes_rx.recv_async().await {
+                        Ok(bytes) => bytes,
+                        Err(_) => {
+                            // Channel closed - PTY has finished, send Exit event
+                            let _ = exit_event_tx.send(TerminalEvent::Exit);
+                            // Notify view to process the Exit event
+                            let _ = this.update(cx, |_view, cx: &mut Context<Self>| {
+                                cx.notify();
+                            });
                             break;
                         }
+                    },
+                };
+
+                // Process bytes and notify the view
+                let result = this.update(cx, |view: &mut Self, cx: &mut Context<Self>| {
+                    if view.paused {
+                        return false;
                     }
-                    Err(_) => {
-                        // Channel closed - PTY has finished, send Exit event
-                        let _ = exit_event_tx.send(TerminalEvent::Exit);
-                        // Notify view to process the Exit event
-                        let _ = this.update(cx, |_view, cx: &mut Context<Self>| {
-                            cx.notify();
-                        });
-                        break;
+                    view.process_output(&bytes, cx);
+                    true
+                });
+                match result {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        // Paused: keep this batch and leave the rest queued in the channel
+                        held = Some(bytes);
+                        if resume_rx.recv_async().await.is_err() {
+                            break;
+                        }
                     }
+                    // View was dropped, exit
+                    Err(_) => break,
                 }
             }
         });
 
-        Self {
+        let mut view = Self {
             state,
             renderer,
             focus_handle,
             stdin_writer,
             event_rx,
+            queued_events: VecDeque::new(),
             config,
-            _reader_task: reader_task,
+            reader_task: Some(reader_task),
             resize_callback: None,
+            layout_callback: None,
             key_handler: None,
-            bell_callback: None,
-            title_callback: None,
-            clipboard_store_callback: None,
-            exit_callback: None,
-        }
+            bell_callbacks: Vec::new(),
+            title_callbacks: Vec::new(),
+            clipboard_store_callbacks: Vec::new(),
+            clipboard_load_callback: None,
+            exit_callbacks: Vec::new(),
+            update_callbacks: Vec::new(),
+            unhandled_sequence_callback: None,
+            scrollback_evict_callback: None,
+            color_change_callback: None,
+            hover_callback: None,
+            hovered_cell: None,
+            input_tap: None,
+            output_tap: None,
+            output_filter: None,
+            content_changed: false,
+            bell_pending: false,
+            exited: false,
+            title: String::new(),
+            title_override: None,
+            title_resumed: false,
+            search: None,
+            last_output_notify: None,
+            deferred_notify: false,
+            grid_layout: Arc::new(parking_lot::Mutex::new(None)),
+            selecting: false,
+            word_anchor: None,
+            press_point: None,
+            last_activity: Instant::now(),
+            cursor_idle_task: None,
+            cursor_blink_task: None,
+            pending_input: Arc::new(parking_lot::Mutex::new(VecDeque::new())),
+            input_closed: Arc::new(AtomicBool::new(false)),
+            paused: false,
+            resume_tx,
+            scroll_remainder: px(0.0),
+            zoom_remainder: px(0.0),
+            sync_timeout_task: None,
+            output_batches: 0,
+        };
+        view.restart_cursor_blink(cx);
+        view
     }
 
     /// Set a callback to be invoked when the terminal is resized.
@@ -551,6 +1387,24 @@ impl TerminalView {
         self
     }
 
+    /// Set a callback for changes to the grid's size and pixel placement.
+    ///
+    /// Unlike the resize callback, it also runs when the grid moves without
+    /// changing size, e.g. when the view grows by less than a cell and
+    /// [`center_grid`](TerminalConfig::center_grid) shifts it. It runs once
+    /// on the first paint.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - A function that will be called with (cols, rows, content_bounds)
+    pub fn with_layout_callback(
+        mut self,
+        callback: impl Fn(usize, usize, Bounds<Pixels>) + 'static,
+    ) -> Self {
+        self.layout_callback = Some(Rc::new(Box::new(callback)));
+        self
+    }
+
     /// Set a callback to intercept key events before terminal processing.
     ///
     /// The callback receives the key event and should return `true` to consume
@@ -581,11 +1435,32 @@ impl TerminalView {
         self
     }
 
+    /// Set a callback that can take over drawing of individual characters.
+    ///
+    /// The callback receives each character with its cell bounds and foreground
+    /// color and returns `true` if it painted the character itself. This works
+    /// like the built-in box-drawing special case, for glyphs such as
+    /// private-use-area prompt symbols.
+    ///
+    /// # Arguments
+    ///
+    /// * `renderer` - A function that draws a character and returns whether it did
+    pub fn with_glyph_renderer(
+        mut self,
+        renderer: impl Fn(char, Bounds<Pixels>, Hsla, &mut Window) -> bool + 'static,
+    ) -> Self {
+        self.renderer.glyph_renderer = Some(Arc::new(Box::new(renderer)));
+        self
+    }
+
     /// Set a callback to be invoked when the terminal bell is triggered.
     ///
     /// The callback receives a mutable reference to the window and context,
     /// allowing you to play a sound or show a visual indicator.
     ///
+    /// Replaces any callbacks registered before; see
+    /// [`add_bell_callback`](Self::add_bell_callback) to register several.
+    ///
     /// # Arguments
     ///
     /// * `callback` - A function that will be called when the bell is triggered
@@ -601,15 +1476,29 @@ impl TerminalView {
         mut self,
         callback: impl Fn(&mut Window, &mut Context<TerminalView>) + 'static,
     ) -> Self {
-        self.bell_callback = Some(Box::new(callback));
+        self.bell_callbacks = vec![Box::new(callback)];
         self
     }
 
+    /// Register an additional bell callback.
+    ///
+    /// Unlike [`with_bell_callback`](Self::with_bell_callback), this keeps the
+    /// callbacks already registered; all of them run in the order they were added.
+    pub fn add_bell_callback(
+        &mut self,
+        callback: impl Fn(&mut Window, &mut Context<TerminalView>) + 'static,
+    ) {
+        self.bell_callbacks.push(Box::new(callback));
+    }
+
     /// Set a callback to be invoked when the terminal title changes.
     ///
     /// The callback receives a mutable reference to the window and context,
     /// along with the new title string.
     ///
+    /// Replaces any callbacks registered before; see
+    /// [`add_title_callback`](Self::add_title_callback) to register several.
+    ///
     /// # Arguments
     ///
     /// * `callback` - A function that will be called with the new title
@@ -625,15 +1514,29 @@ impl TerminalView {
         mut self,
         callback: impl Fn(&mut Window, &mut Context<TerminalView>, &str) + 'static,
     ) -> Self {
-        self.title_callback = Some(Box::new(callback));
+        self.title_callbacks = vec![Box::new(callback)];
         self
     }
 
+    /// Register an additional title callback.
+    ///
+    /// Unlike [`with_title_callback`](Self::with_title_callback), this keeps the
+    /// callbacks already registered; all of them run in the order they were added.
+    pub fn add_title_callback(
+        &mut self,
+        callback: impl Fn(&mut Window, &mut Context<TerminalView>, &str) + 'static,
+    ) {
+        self.title_callbacks.push(Box::new(callback));
+    }
+
     /// Set a callback to be invoked when the terminal wants to store data to the clipboard.
     ///
     /// The callback receives a mutable reference to the window and context,
     /// along with the text to store. This is typically triggered by OSC 52 escape sequences.
     ///
+    /// Replaces any callbacks registered before; see
+    /// [`add_clipboard_store_callback`](Self::add_clipboard_store_callback) to register several.
+    ///
     /// # Arguments
     ///
     /// * `callback` - A function that will be called with the text to store
@@ -649,7 +1552,37 @@ impl TerminalView {
         mut self,
         callback: impl Fn(&mut Window, &mut Context<TerminalView>, &str) + 'static,
     ) -> Self {
-        self.clipboard_store_callback = Some(Box::new(callback));
+        self.clipboard_store_callbacks = vec![Box::new(callback)];
+        self
+    }
+
+    /// Register an additional clipboard store callback.
+    ///
+    /// Unlike [`with_clipboard_store_callback`](Self::with_clipboard_store_callback),
+    /// this keeps the callbacks already registered; all of them run in the order
+    /// they were added.
+    pub fn add_clipboard_store_callback(
+        &mut self,
+        callback: impl Fn(&mut Window, &mut Context<TerminalView>, &str) + 'static,
+    ) {
+        self.clipboard_store_callbacks.push(Box::new(callback));
+    }
+
+    /// Set a callback that answers clipboard queries from the terminal.
+    ///
+    /// Programs query the clipboard with OSC 52 and a `?` payload. The text
+    /// returned by the callback is sent back to the program in the matching
+    /// OSC 52 reply. Queries are only accepted when
+    /// [`TerminalConfig::allow_clipboard_read`] is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - A function returning the clipboard text, or `None` to ignore the query
+    pub fn with_clipboard_load_callback(
+        mut self,
+        callback: impl Fn(&mut Window, &mut Context<TerminalView>) -> Option<String> + 'static,
+    ) -> Self {
+        self.clipboard_load_callback = Some(Box::new(callback));
         self
     }
 
@@ -658,6 +1591,9 @@ impl TerminalView {
     /// The callback receives a mutable reference to the window and context,
     /// allowing you to close the terminal view or show an exit message.
     ///
+    /// Replaces any callbacks registered before; see
+    /// [`add_exit_callback`](Self::add_exit_callback) to register several.
+    ///
     /// # Arguments
     ///
     /// * `callback` - A function that will be called when the process exits
@@ -673,171 +1609,1613 @@ impl TerminalView {
         mut self,
         callback: impl Fn(&mut Window, &mut Context<TerminalView>) + 'static,
     ) -> Self {
-        self.exit_callback = Some(Box::new(callback));
+        self.exit_callbacks = vec![Box::new(callback)];
+        self
+    }
+
+    /// Register an additional exit callback.
+    ///
+    /// Unlike [`with_exit_callback`](Self::with_exit_callback), this keeps the
+    /// callbacks already registered; all of them run in the order they were added.
+    pub fn add_exit_callback(
+        &mut self,
+        callback: impl Fn(&mut Window, &mut Context<TerminalView>) + 'static,
+    ) {
+        self.exit_callbacks.push(Box::new(callback));
+    }
+
+    /// Set a callback to be invoked when output changes the grid.
+    ///
+    /// The callback runs on the first frame after new PTY output was processed,
+    /// which makes it a single hook for mirroring or diffing the screen without
+    /// polling. Repaints that don't involve new output do not trigger it.
+    ///
+    /// Replaces any callbacks registered before; see
+    /// [`add_update_callback`](Self::add_update_callback) to register several.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - A function that will be called after the grid changed
+    pub fn with_update_callback(
+        mut self,
+        callback: impl Fn(&mut Window, &mut Context<TerminalView>) + 'static,
+    ) -> Self {
+        self.update_callbacks = vec![Box::new(callback)];
         self
     }
 
-    /// Background thread that reads from stdout.
+    /// Register an additional update callback.
+    ///
+    /// Unlike [`with_update_callback`](Self::with_update_callback), this keeps the
+    /// callbacks already registered; all of them run in the order they were added.
+    pub fn add_update_callback(
+        &mut self,
+        callback: impl Fn(&mut Window, &mut Context<TerminalView>) + 'static,
+    ) {
+        self.update_callbacks.push(Box::new(callback));
+    }
+
+    /// Set a callback for escape sequences the terminal ignores.
+    ///
+    /// Setting it makes the terminal scan output for sequences alacritty
+    /// doesn't implement (see [`crate::diagnostics`]), which costs a second
+    /// parse of all output, so it's meant for debugging rendering problems.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - A function that will be called with each ignored sequence
+    pub fn with_unhandled_sequence_callback(
+        mut self,
+        callback: impl Fn(&mut Window, &mut Context<TerminalView>, &[u8]) + 'static,
+    ) -> Self {
+        self.state.set_report_unhandled_sequences(true);
+        self.unhandled_sequence_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Set a callback for lines evicted from the full scrollback.
+    ///
+    /// Setting it makes the terminal collect the lines it drops over
+    /// [`TerminalConfig::scrollback`], see
+    /// [`TerminalState::set_collect_evicted_lines`] for the details.
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - A function that will be called with the evicted lines
+    pub fn with_scrollback_evict_callback(
+        mut self,
+        callback: impl Fn(&[String]) + 'static,
+    ) -> Self {
+        self.state.set_collect_evicted_lines(true);
+        self.scrollback_evict_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Set the callback for colors changed by escape sequences.
+    ///
+    /// Changes are found by comparing the terminal's color table before and
+    /// after each batch of output, so setting a color to the value it already
+    /// has isn't reported. See [`ColorChangeCallback`].
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - A function that will be called with each changed color
+    pub fn with_color_change_callback(
+        mut self,
+        callback: impl Fn(DynamicColor, Option<Rgb>) + 'static,
+    ) -> Self {
+        self.color_change_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Set the callback for the cell under the mouse pointer.
+    ///
+    /// See [`HoverCallback`].
+    ///
+    /// # Arguments
+    ///
+    /// * `callback` - A function that will be called with each newly hovered cell
+    pub fn with_hover_callback(mut self, callback: impl Fn(AlacPoint) + 'static) -> Self {
+        self.hover_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Set a tap that sees every byte written to the PTY.
+    ///
+    /// The tap is called from [`send_input`](Self::send_input) with the bytes
+    /// as given, before they are written (or queued, for large writes).
+    ///
+    /// # Arguments
+    ///
+    /// * `tap` - A function that will be called with each write
+    pub fn with_input_tap(mut self, tap: impl Fn(&[u8]) + 'static) -> Self {
+        self.input_tap = Some(Box::new(tap));
+        self
+    }
+
+    /// Set a tap that sees every byte read from the PTY.
+    ///
+    /// The tap is called with each batch of output just before it is parsed,
+    /// so output held back while [paused](Self::set_paused) reaches it once
+    /// processing resumes.
+    ///
+    /// # Arguments
+    ///
+    /// * `tap` - A function that will be called with each batch of output
+    pub fn with_output_tap(mut self, tap: impl Fn(&[u8]) + 'static) -> Self {
+        self.output_tap = Some(Box::new(tap));
+        self
+    }
+
+    /// Set a filter that rewrites the PTY output before it is parsed.
+    ///
+    /// The filter sees each batch after the [output tap](Self::with_output_tap),
+    /// which keeps recording the unfiltered bytes. See [`OutputFilter`].
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - A function returning the bytes to parse for each batch
+    pub fn with_output_filter(mut self, filter: impl Fn(&[u8]) -> Cow<'_, [u8]> + 'static) -> Self {
+        self.output_filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Background thread that reads from stdout.
+    ///
+    /// This function runs in a background thread, continuously reading bytes
+    /// from the stdout reader and sending them through the async channel.
+    /// The async channel allows the main async task to be woken up immediately
+    /// when data arrives (push-based). Each read asks for up to `buffer_size`
+    /// bytes, see [`TerminalConfig::read_buffer_size`]. Sending blocks while
+    /// the channel is full, which holds off the next read.
+    fn read_stdout_blocking<R: Read + Send + 'static>(
+        mut stdout_reader: R,
+        bytes_tx: flume::Sender<Vec<u8>>,
+        buffer_size: usize,
+    ) {
+        let mut buffer = vec![0u8; buffer_size.max(1)];
+
+        loop {
+            match stdout_reader.read(&mut buffer) {
+                Ok(0) => {
+                    // EOF - channel will be dropped, signaling completion
+                    break;
+                }
+                Ok(n) => {
+                    // Send bytes to the async task
+                    let bytes = buffer[..n].to_vec();
+                    if bytes_tx.send(bytes).is_err() {
+                        break; // Channel closed
+                    }
+                }
+                Err(_) => {
+                    // Read error
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Feed a batch of PTY output to the terminal and schedule a repaint.
+    fn process_output(&mut self, bytes: &[u8], cx: &mut Context<Self>) {
+        self.state.sync_size();
+        self.output_batches += 1;
+        if let Some(ref tap) = self.output_tap {
+            tap(bytes);
+        }
+        let bytes = match self.output_filter {
+            Some(ref filter) => filter(bytes),
+            None => Cow::Borrowed(bytes),
+        };
+        self.update_grid(|state| state.process_bytes(&bytes), cx);
+        self.write_replies(cx);
+
+        if let Some(ref callback) = self.scrollback_evict_callback {
+            let lines = self.state.take_evicted_lines();
+            if !lines.is_empty() {
+                callback(&lines);
+            }
+        }
+    }
+
+    /// Write the terminal's replies to queries, such as `CSI 18 t`, to the PTY.
+    ///
+    /// Programs may block until they get an answer (fish waits for the device
+    /// attributes at startup), so replies are written as soon as the output is
+    /// parsed rather than on the next repaint, which never comes for a hidden
+    /// terminal. Other events stay queued for the next render.
+    fn write_replies(&mut self, cx: &mut Context<Self>) {
+        let events: Vec<_> = self.event_rx.try_iter().collect();
+        for event in events {
+            match event {
+                TerminalEvent::PtyWrite(reply) => self.send_input(reply.as_bytes(), cx),
+                event => self.queued_events.push_back(event),
+            }
+        }
+    }
+
+    /// Apply an update to the grid and schedule a repaint.
+    ///
+    /// While the program holds a synchronized update (DECSET 2026) open, the
+    /// repaint waits for it to end, so a half-drawn frame is never shown.
+    fn update_grid(&mut self, update: impl FnOnce(&mut TerminalState), cx: &mut Context<Self>) {
+        let was_alt_screen = self.state.mode().contains(TermMode::ALT_SCREEN);
+        let display_offset = self.state.display_offset();
+        let history_size = self.state.history_size();
+        let colors = self
+            .color_change_callback
+            .as_ref()
+            .map(|_| self.state.with_term(|term| *term.colors()));
+        update(&mut self.state);
+        self.content_changed = true;
+
+        if let (Some(callback), Some(old)) = (&self.color_change_callback, colors) {
+            let new = self.state.with_term(|term| *term.colors());
+            for (color, rgb) in color_changes(&old, &new) {
+                callback(color, rgb);
+            }
+        }
+
+        let new_history = self.state.history_size().saturating_sub(history_size);
+        if let Some(scroll) = self
+            .config
+            .scroll_on_output
+            .scroll_after_output(display_offset, new_history)
+        {
+            self.state.with_term_mut(|term| term.scroll_display(scroll));
+        }
+
+        // Switching screens swaps out every cell the selection pointed at
+        let alt_screen_changed = self.state.mode().contains(TermMode::ALT_SCREEN) != was_alt_screen;
+        if alt_screen_changed || self.config.clear_selection_on_output {
+            self.reset_selection();
+        }
+        if alt_screen_changed {
+            self.renderer.selection_cursor = None;
+        }
+
+        // Re-searching the whole scrollback per read would be far too slow, so
+        // it waits for the next frame
+        if let Some(search) = self.search.as_mut() {
+            search.stale = true;
+        }
+
+        match self.state.sync_update_deadline() {
+            Some(deadline) => self.schedule_sync_timeout(deadline, cx),
+            None => {
+                self.sync_timeout_task = None;
+                self.notify_output(cx);
+            }
+        }
+    }
+
+    /// End the synchronized update and paint once its deadline passes.
+    fn schedule_sync_timeout(&mut self, deadline: Instant, cx: &mut Context<Self>) {
+        let delay = deadline.saturating_duration_since(Instant::now());
+        self.sync_timeout_task = Some(cx.spawn(
+            async move |this: WeakEntity<Self>, cx: &mut AsyncApp| {
+                cx.background_executor().timer(delay).await;
+                let _ = this.update(cx, |view: &mut Self, cx: &mut Context<Self>| {
+                    view.sync_timeout_task = None;
+                    view.update_grid(TerminalState::end_sync_update, cx);
+                    view.write_replies(cx);
+                });
+            },
+        ));
+    }
+
+    /// Request a repaint after PTY output, respecting [`TerminalConfig::max_fps`].
+    ///
+    /// If the last output-driven repaint was recent, a single deferred repaint is
+    /// scheduled for the end of the frame interval instead, so floods of output
+    /// are bounded while isolated updates (e.g. typing echo) paint immediately.
+    fn notify_output(&mut self, cx: &mut Context<Self>) {
+        let Some(fps) = self.config.max_fps.filter(|fps| *fps > 0) else {
+            cx.notify();
+            return;
+        };
+
+        if self.deferred_notify {
+            return;
+        }
+
+        let frame_interval = Duration::from_secs_f64(1.0 / fps as f64);
+        let now = Instant::now();
+        let elapsed = self.last_output_notify.map(|last| now.duration_since(last));
+
+        match elapsed {
+            Some(elapsed) if elapsed < frame_interval => {
+                self.deferred_notify = true;
+                let delay = frame_interval - elapsed;
+                cx.spawn(async move |this: WeakEntity<Self>, cx: &mut AsyncApp| {
+                    cx.background_executor().timer(delay).await;
+                    let _ = this.update(cx, |view: &mut Self, cx: &mut Context<Self>| {
+                        view.deferred_notify = false;
+                        view.last_output_notify = Some(Instant::now());
+                        cx.notify();
+                    });
+                })
+                .detach();
+            }
+            _ => {
+                self.last_output_notify = Some(now);
+                cx.notify();
+            }
+        }
+    }
+
+    /// Handle keyboard input events.
+    ///
+    /// Converts GPUI keystrokes to terminal escape sequences and writes them
+    /// to the stdin writer. If a key handler is set and returns true, the event
+    /// is consumed and not sent to the terminal.
+    ///
+    /// Shift+PageUp and Shift+PageDown scroll the scrollback by a page instead,
+    /// except on the alternate screen. With [`TerminalConfig::clipboard_shortcuts`],
+    /// Ctrl+Shift+C copies the selection and Ctrl+Shift+V pastes the clipboard.
+    fn on_key_down(&mut self, event: &KeyDownEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        self.last_activity = Instant::now();
+        self.renderer.cursor_visible = true;
+
+        // Check if key handler wants to consume this event
+        if let Some(ref handler) = self.key_handler
+            && handler(event)
+        {
+            return; // Event consumed by handler
+        }
+
+        // Keyboard selection takes every key while it's active
+        if self.renderer.selection_cursor.is_some() {
+            self.on_selection_mode_key(&event.keystroke, cx);
+            return;
+        }
+
+        let keystroke = &event.keystroke;
+        if self.config.clipboard_shortcuts
+            && let Some(shortcut) = clipboard_shortcut(keystroke)
+        {
+            match shortcut {
+                ClipboardShortcut::Copy => {
+                    if let Some(text) = self.selection_text() {
+                        cx.write_to_clipboard(ClipboardItem::new_string(text));
+                    }
+                }
+                ClipboardShortcut::Paste => {
+                    if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
+                        self.paste(&text, cx);
+                    }
+                }
+            }
+            return;
+        }
+
+        // Shift+PageUp/PageDown page through the scrollback; on the alternate
+        // screen there is none, so the program gets the keys instead
+        let modifiers = &keystroke.modifiers;
+        if modifiers.shift
+            && !(modifiers.control || modifiers.alt || modifiers.platform)
+            && !self.state.mode().contains(TermMode::ALT_SCREEN)
+        {
+            let scroll = match keystroke.key.as_str() {
+                "pageup" => Some(Scroll::PageUp),
+                "pagedown" => Some(Scroll::PageDown),
+                _ => None,
+            };
+            if let Some(scroll) = scroll {
+                self.scroll_display(scroll, cx);
+                return;
+            }
+        }
+
+        let mode = self.state.mode();
+        let bytes = modify_other_keys_to_bytes(keystroke, self.state.modify_other_keys())
+            .or_else(|| match keystroke.key.as_str() {
+                // Keys whose bytes depend on the config
+                "enter" => Some(enter_bytes(self.config.enter_mode, mode).to_vec()),
+                "backspace" => {
+                    Some(backspace_bytes(&keystroke.modifiers, self.config.ctrl_backspace).to_vec())
+                }
+                _ => None,
+            })
+            .or_else(|| keystroke_to_bytes(keystroke, mode));
+        if let Some(bytes) = bytes {
+            self.send_input(&bytes, cx);
+        }
+    }
+
+    /// Write bytes to the terminal process, as if typed.
+    ///
+    /// Small writes go straight to the PTY. Input larger than
+    /// [`TerminalConfig::write_chunk_size`] is split into chunks that a
+    /// background task writes one at a time, yielding in between, so a
+    /// multi-megabyte paste doesn't freeze the UI while the PTY drains.
+    /// Input sent while chunks are pending is queued behind them, keeping
+    /// everything in order, without waiting for the chunk being written.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The raw bytes to send
+    /// * `cx` - The context used to spawn the background writer
+    pub fn send_input(&mut self, bytes: &[u8], cx: &mut Context<Self>) {
+        if self.exited || self.input_closed.load(Ordering::Acquire) {
+            return; // Shut down or exited
+        }
+
+        // Only bytes that reach the process are tapped; the tap runs unlocked
+        if let Some(ref tap) = self.input_tap {
+            tap(bytes);
+        }
+
+        let chunk_size = self.config.write_chunk_size.unwrap_or(usize::MAX).max(1);
+        let mut pending = self.pending_input.lock();
+        if !pending.is_empty() {
+            // The background writer is still draining and picks these up
+            pending.extend(bytes.chunks(chunk_size).map(<[u8]>::to_vec));
+            return;
+        }
+
+        if bytes.len() <= chunk_size {
+            // Released first: the background writer locks the writer, then the queue
+            drop(pending);
+            if let Some(writer) = self.stdin_writer.lock().as_mut() {
+                let _ = writer.write_all(bytes);
+                let _ = writer.flush();
+            }
+            return;
+        }
+
+        pending.extend(bytes.chunks(chunk_size).map(<[u8]>::to_vec));
+        drop(pending);
+
+        let writer = self.stdin_writer.clone();
+        let pending = self.pending_input.clone();
+        let closed = self.input_closed.clone();
+        let executor = cx.background_executor().clone();
+        cx.background_spawn(async move {
+            while !closed.load(Ordering::Acquire) {
+                {
+                    let mut writer = writer.lock();
+                    let Some(writer) = writer.as_mut() else {
+                        break;
+                    };
+                    let Some(chunk) = pending.lock().pop_front() else {
+                        break;
+                    };
+                    let _ = writer.write_all(&chunk);
+                    let _ = writer.flush();
+                }
+                // Let other tasks (and the UI) run between chunks
+                executor.timer(Duration::ZERO).await;
+            }
+        })
+        .detach();
+    }
+
+    /// Write input, then capture the screen once the output has settled.
+    ///
+    /// Meant for scripted end-to-end tests of programs running in the
+    /// terminal. Output counts as settled once no new bytes arrived for
+    /// `settle`, so it should be longer than the program takes to react.
+    /// The capture happens after `timeout` at the latest, even if output keeps
+    /// streaming in.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The bytes to send, as with [`send_input`](Self::send_input)
+    /// * `settle` - How long output must stay quiet
+    /// * `timeout` - The longest time to wait before capturing
+    /// * `cx` - The context used to write the input and run the wait
+    ///
+    /// # Returns
+    ///
+    /// A task resolving to the screen text, as from
+    /// [`TerminalState::visible_text`]. It is empty if the view was dropped.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let screen = terminal
+    ///     .update(cx, |terminal, cx| {
+    ///         terminal.send_and_capture(b"ls\r", Duration::from_millis(200), Duration::from_secs(5), cx)
+    ///     })
+    ///     .await;
+    /// assert!(screen.contains("Cargo.toml"));
+    /// ```
+    pub fn send_and_capture(
+        &mut self,
+        input: &[u8],
+        settle: Duration,
+        timeout: Duration,
+        cx: &mut Context<Self>,
+    ) -> Task<String> {
+        self.send_input(input, cx);
+
+        let deadline = Instant::now() + timeout;
+        let mut seen = self.output_batches;
+        cx.spawn(async move |this: WeakEntity<Self>, cx: &mut AsyncApp| {
+            loop {
+                let wait = settle.min(deadline.saturating_duration_since(Instant::now()));
+                cx.background_executor().timer(wait).await;
+                let Ok(batches) = this.update(cx, |view: &mut Self, _| view.output_batches) else {
+                    return String::new();
+                };
+                if batches == seen || Instant::now() >= deadline {
+                    break;
+                }
+                seen = batches;
+            }
+            this.update(cx, |view: &mut Self, _| view.state.visible_text())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Stop talking to the terminal process and release the I/O streams.
+    ///
+    /// Drops the stdin writer (along with input still queued for it) and
+    /// cancels the task processing output, so the screen keeps its last
+    /// contents and no exit callback fires. The stdout reader thread is
+    /// blocked in `read` and exits once that read returns, which happens when
+    /// the PTY is closed or the child exits; kill the child to release it
+    /// right away. Further input is ignored.
+    ///
+    /// Call this when tearing down a pane; dropping the view has the same
+    /// effect on the reader task but keeps the writer alive until then.
+    pub fn shutdown(&mut self) {
+        self.reader_task = None;
+        self.input_closed.store(true, Ordering::Release);
+        self.stdin_writer.lock().take();
+        self.pending_input.lock().clear();
+        self.cursor_idle_task = None;
+        self.cursor_blink_task = None;
+    }
+
+    /// Whether [`shutdown`](Self::shutdown) has been called.
+    pub fn is_shut_down(&self) -> bool {
+        self.reader_task.is_none()
+    }
+
+    /// Handle mouse down events.
+    ///
+    /// Focuses the terminal and starts a new selection at the clicked cell.
+    /// The click count picks the selection type; holding Alt starts a block
+    /// selection instead. A single click only clears the selection, and a new
+    /// one begins once the drag leaves the clicked cell.
+    fn on_mouse_down(
+        &mut self,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Request focus when clicking the terminal
+        window.focus(&self.focus_handle);
+        self.last_activity = Instant::now();
+
+        if let Some(point) = self.grid_point_at(event.position) {
+            let selection_type = if event.modifiers.alt {
+                SelectionType::Block
+            } else {
+                selection_type_from_clicks(event.click_count)
+            };
+            let (start, end) = if selection_type == SelectionType::Word {
+                self.state.word_bounds(point, &self.config.word_separators)
+            } else {
+                (point, point)
+            };
+            self.word_anchor = (selection_type == SelectionType::Word).then_some((start, end));
+            let deferred = matches!(selection_type, SelectionType::Simple | SelectionType::Block);
+            self.press_point = deferred.then_some(point);
+            self.renderer.selection =
+                (!deferred).then(|| Selection::new(start, end, selection_type));
+            self.selecting = true;
+        }
+        cx.notify();
+
+        // TODO: Send mouse reports if mouse tracking is enabled
+    }
+
+    /// Handle mouse up events.
+    ///
+    /// Ends the drag; the selection stays in place until the next click.
+    fn on_mouse_up(
+        &mut self,
+        _event: &MouseUpEvent,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) {
+        self.selecting = false;
+
+        // TODO: Copy selection to clipboard if configured
+    }
+
+    /// Handle mouse move events.
+    ///
+    /// Extends the selection while the left button is held. Holding Alt during
+    /// the drag turns it into a block selection.
+    fn on_mouse_move(
+        &mut self,
+        event: &MouseMoveEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.config.focus_follows_mouse
+            && event.pressed_button.is_none()
+            && !self.focus_handle.is_focused(window)
+        {
+            window.focus(&self.focus_handle);
+        }
+
+        if let Some(ref callback) = self.hover_callback {
+            let point = self.grid_point_at(event.position);
+            if let Some(point) = point.filter(|&point| Some(point) != self.hovered_cell) {
+                self.hovered_cell = Some(point);
+                callback(point);
+            }
+        }
+
+        if !self.selecting || event.pressed_button != Some(MouseButton::Left) {
+            return;
+        }
+
+        let Some(point) = self.grid_point_at(event.position) else {
+            return;
+        };
+        // Word selections grow a whole word at a time, keeping the clicked word
+        let word_drag = self
+            .word_anchor
+            .filter(|_| !event.modifiers.alt)
+            .map(|anchor| {
+                (
+                    anchor,
+                    self.state.word_bounds(point, &self.config.word_separators),
+                )
+            });
+
+        if self.renderer.selection.is_none() {
+            match self.press_point {
+                Some(origin) if origin != point => {
+                    self.renderer.selection =
+                        Some(Selection::new(origin, origin, SelectionType::Simple));
+                }
+                _ => return,
+            }
+        }
+        let Some(selection) = self.renderer.selection.as_mut() else {
+            return;
+        };
+
+        let previous = selection.clone();
+        if let Some(((anchor_start, anchor_end), (word_start, word_end))) = word_drag {
+            selection.selection_type = SelectionType::Word;
+            if point < anchor_start {
+                selection.start = anchor_end;
+                selection.end = word_start;
+            } else {
+                selection.start = anchor_start;
+                selection.end = word_end.max(anchor_end);
+            }
+        } else {
+            if event.modifiers.alt {
+                selection.selection_type = SelectionType::Block;
+            } else if selection.selection_type == SelectionType::Block {
+                selection.selection_type = SelectionType::Simple;
+            }
+            selection.end = point;
+        }
+
+        if *selection != previous {
+            cx.notify();
+        }
+
+        // TODO: Send mouse motion reports if mouse tracking is enabled
+    }
+
+    /// Convert a window position to a grid point, accounting for scrollback.
+    ///
+    /// Positions outside the grid are clamped to the nearest cell. Returns
+    /// `None` before the terminal has been painted for the first time.
+    fn grid_point_at(&self, position: Point<Pixels>) -> Option<AlacPoint> {
+        let layout = (*self.grid_layout.lock())?;
+        let cell = pixel_to_cell(
+            position,
+            layout.origin,
+            layout.cell_width,
+            layout.cell_height,
+        );
+
+        self.state.with_term(|term| {
+            let grid = term.grid();
+            let row = (cell.line.0 as usize).min(grid.screen_lines().saturating_sub(1));
+            let col = cell.column.0.min(grid.columns().saturating_sub(1));
+            let line = AlacLine(row as i32 - grid.display_offset() as i32);
+            Some(AlacPoint::new(line, AlacColumn(col)))
+        })
+    }
+
+    /// Get the text of the row under a window position.
+    ///
+    /// Useful for hover tooltips and click-to-act features. The row is looked
+    /// up in the scrollback when the display is scrolled, and its trailing
+    /// blanks are trimmed.
+    ///
+    /// # Returns
+    ///
+    /// The row's text, or `None` if the position is above or below the grid or
+    /// the terminal hasn't been painted yet.
+    pub fn line_text_at_pixel(&self, position: Point<Pixels>) -> Option<String> {
+        let layout = (*self.grid_layout.lock())?;
+        if position.y < layout.origin.y {
+            return None;
+        }
+        let cell = pixel_to_cell(
+            position,
+            layout.origin,
+            layout.cell_width,
+            layout.cell_height,
+        );
+        if cell.line.0 as usize >= self.state.rows() {
+            return None;
+        }
+
+        let line = cell.line - self.state.display_offset() as i32;
+        let point = AlacPoint::new(line, AlacColumn(0));
+        let selection = Selection::new(point, point, SelectionType::Line);
+        Some(self.state.selection_text(&selection, true))
+    }
+
+    /// Get the current selection, in grid coordinates.
+    pub fn selection(&self) -> Option<&Selection> {
+        self.renderer.selection.as_ref()
+    }
+
+    /// Compute the window-space rectangles a selection covers, one per row.
+    ///
+    /// Uses the grid placement from the last paint, so the rectangles line up
+    /// with the built-in highlight, e.g. for a custom highlight or a tooltip
+    /// anchored to the selection. Block selections yield the same column range
+    /// on every row. Rows outside the viewport are left out, and the result is
+    /// empty before the terminal has been painted.
+    ///
+    /// # Arguments
+    ///
+    /// * `selection` - The selection, in grid coordinates
+    pub fn selection_pixel_rects(&self, selection: &Selection) -> Vec<Bounds<Pixels>> {
+        let Some(layout) = *self.grid_layout.lock() else {
+            return Vec::new();
+        };
+        let cols = self.state.cols();
+
+        (0..self.state.rows())
+            .filter_map(|row| {
+                let line = self
+                    .state
+                    .to_absolute_point(AlacPoint::new(row, AlacColumn(0)))
+                    .line;
+                let (start_col, end_col) = selection.columns_on_line(line, cols)?;
+                Some(Bounds {
+                    origin: Point {
+                        x: layout.column_x(start_col),
+                        y: layout.origin.y + layout.cell_height * (row as f32),
+                    },
+                    size: Size {
+                        width: layout.column_x(end_col) - layout.column_x(start_col),
+                        height: layout.cell_height,
+                    },
+                })
+            })
+            .collect()
+    }
+
+    /// Get the text covered by the current selection.
+    ///
+    /// Block selections yield the selected column range of every row, joined
+    /// with newlines. Trailing spaces are trimmed per
+    /// [`TerminalConfig::copy_trim_trailing`].
+    pub fn selection_text(&self) -> Option<String> {
+        self.renderer.selection.as_ref().map(|selection| {
+            self.state
+                .selection_text(selection, self.config.copy_trim_trailing)
+        })
+    }
+
+    /// Remove the current selection.
+    pub fn clear_selection(&mut self, cx: &mut Context<Self>) {
+        self.reset_selection();
+        cx.notify();
+    }
+
+    /// Start keyboard selection mode.
+    ///
+    /// A selection cursor appears on the terminal cursor (or the bottom line of
+    /// the viewport, when scrolled up) and keys stop going to the program:
+    ///
+    /// | Key | Action |
+    /// |-----|--------|
+    /// | Arrow keys, Home, End | Move the selection cursor |
+    /// | Shift + movement | Extend the selection from the cursor |
+    /// | Enter | Copy the selection to the clipboard and leave the mode |
+    /// | Escape | Leave the mode |
+    ///
+    /// Moving past the edge of the viewport scrolls it. Any previous selection
+    /// is cleared. Switching to or from the alternate screen leaves the mode.
+    pub fn enter_selection_mode(&mut self, cx: &mut Context<Self>) {
+        let cursor = self.state.with_term(|term| term.grid().cursor.point);
+        let bottom = AlacLine(self.state.rows() as i32 - 1 - self.state.display_offset() as i32);
+        self.reset_selection();
+        self.renderer.selection_cursor =
+            Some(AlacPoint::new(cursor.line.min(bottom), cursor.column));
+        cx.notify();
+    }
+
+    /// Leave keyboard selection mode, keeping the selection.
+    pub fn exit_selection_mode(&mut self, cx: &mut Context<Self>) {
+        self.renderer.selection_cursor = None;
+        cx.notify();
+    }
+
+    /// Get the decorations currently drawn over the terminal contents.
+    ///
+    /// Useful to decide what a key like Escape should do, e.g. clear the
+    /// decorations if there are any and send the key to the program otherwise.
+    pub fn decorations(&self) -> Decorations {
+        Decorations {
+            selection: self.renderer.selection.is_some(),
+            search: self.search.is_some(),
+            selection_mode: self.in_selection_mode(),
+        }
+    }
+
+    /// Remove every decoration: the selection, the search and its
+    /// highlights, and keyboard selection mode.
+    pub fn clear_decorations(&mut self, cx: &mut Context<Self>) {
+        self.reset_selection();
+        self.search = None;
+        self.renderer.search_matches.clear();
+        self.renderer.active_match = None;
+        self.renderer.selection_cursor = None;
+        cx.notify();
+    }
+
+    /// Whether keyboard selection mode is active.
+    pub fn in_selection_mode(&self) -> bool {
+        self.renderer.selection_cursor.is_some()
+    }
+
+    /// Handle a key in keyboard selection mode.
+    fn on_selection_mode_key(&mut self, keystroke: &Keystroke, cx: &mut Context<Self>) {
+        let Some(cursor) = self.renderer.selection_cursor else {
+            return;
+        };
+        match keystroke.key.as_str() {
+            "enter" => {
+                if let Some(text) = self.selection_text() {
+                    cx.write_to_clipboard(ClipboardItem::new_string(text));
+                }
+                self.exit_selection_mode(cx);
+                return;
+            }
+            "escape" => {
+                self.exit_selection_mode(cx);
+                return;
+            }
+            _ => {}
+        }
+
+        let (lines, cols) = self.state.with_term(|term| {
+            let grid = term.grid();
+            (grid.topmost_line()..=grid.bottommost_line(), grid.columns())
+        });
+        let Some(point) = step_selection_cursor(cursor, &keystroke.key, cols, lines) else {
+            return;
+        };
+        if keystroke.modifiers.shift {
+            self.renderer
+                .selection
+                .get_or_insert_with(|| Selection::new(cursor, cursor, SelectionType::Simple))
+                .end = point;
+        } else {
+            self.renderer.selection = None;
+        }
+        self.renderer.selection_cursor = Some(point);
+
+        // Scroll the cursor into view
+        let top = -(self.state.display_offset() as i32);
+        let bottom = top + self.state.rows() as i32 - 1;
+        if point.line.0 < top {
+            self.scroll_display(Scroll::Delta(top - point.line.0), cx);
+        } else if point.line.0 > bottom {
+            self.scroll_display(Scroll::Delta(bottom - point.line.0), cx);
+        }
+        cx.notify();
+    }
+
+    /// Drop the selection and end any drag in progress.
+    fn reset_selection(&mut self) {
+        self.renderer.selection = None;
+        self.selecting = false;
+        self.word_anchor = None;
+        self.press_point = None;
+    }
+
+    /// Handle scroll events.
+    ///
+    /// Deltas are scaled by [`TerminalConfig::scroll_multiplier`] and
+    /// accumulated until they add up to whole lines, so small trackpad
+    /// movements still scroll proportionally. The lines are reported to the
+    /// program when it tracks the mouse or uses the alternate screen, and
+    /// scroll the scrollback otherwise.
+    ///
+    /// With [`TerminalConfig::ctrl_scroll_zoom`], Ctrl+scroll zooms instead,
+    /// unless the program tracks the mouse: every line's worth of scrolling
+    /// changes the font size by 1px.
+    fn on_scroll(
+        &mut self,
+        event: &ScrollWheelEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(layout) = *self.grid_layout.lock() else {
+            return;
+        };
+
+        let delta = event.delta.pixel_delta(layout.cell_height).y * self.config.scroll_multiplier;
+        if self.config.ctrl_scroll_zoom
+            && event.modifiers.control
+            && !self.state.mode().intersects(TermMode::MOUSE_MODE)
+        {
+            if matches!(event.touch_phase, TouchPhase::Started)
+                || delta.signum() != self.zoom_remainder.signum()
+            {
+                self.zoom_remainder = px(0.0);
+            }
+            self.zoom_remainder += delta;
+
+            let steps = (self.zoom_remainder / layout.cell_height).trunc();
+            if steps != 0.0 {
+                self.zoom_remainder -= layout.cell_height * steps;
+                self.adjust_font_size(px(steps), cx);
+            }
+            return;
+        }
+
+        // Start fresh for each gesture and whenever the direction changes
+        if matches!(event.touch_phase, TouchPhase::Started)
+            || delta.signum() != self.scroll_remainder.signum()
+        {
+            self.scroll_remainder = px(0.0);
+        }
+        self.scroll_remainder += delta;
+
+        let lines = (self.scroll_remainder / layout.cell_height).trunc();
+        if lines == 0.0 {
+            return;
+        }
+        self.scroll_remainder -= layout.cell_height * lines;
+        let lines = lines as i32;
+
+        let mode = self.state.mode();
+        let display_offset = self.state.display_offset() as i32;
+        let report = self.grid_point_at(event.position).and_then(|point| {
+            // Reports use viewport coordinates
+            let point = AlacPoint::new(point.line + display_offset, point.column);
+            let modifiers = encode_modifiers(
+                event.modifiers.shift,
+                event.modifiers.alt,
+                event.modifiers.control,
+            );
+            scroll_report(lines, point, modifiers, mode)
+        });
+
+        match report {
+            Some(bytes) => self.send_input(&bytes, cx),
+            None => self.scroll_display(Scroll::Delta(lines), cx),
+        }
+    }
+
+    /// Process pending terminal events.
+    ///
+    /// This method drains all available events from the event receiver
+    /// and handles them appropriately. Note: bytes are processed in the
+    /// async reader task, not here.
+    fn process_events(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let focused = self.focus_handle.is_focused(window);
+        if focused {
+            self.bell_pending = false;
+        }
+
+        if self.title_resumed {
+            self.title_resumed = false;
+            for callback in &self.title_callbacks {
+                callback(window, cx, &self.title);
+            }
+        }
+
+        // Process terminal events (from alacritty event proxy), oldest first
+        while let Some(event) = self
+            .queued_events
+            .pop_front()
+            .or_else(|| self.event_rx.try_recv().ok())
+        {
+            match event {
+                TerminalEvent::Wakeup => {
+                    // Terminal has new content - already handled by async task
+                }
+                TerminalEvent::Bell => {
+                    if !focused {
+                        self.bell_pending = true;
+                    }
+                    #[cfg(feature = "audible-bell")]
+                    if self.config.audible_bell {
+                        system_beep();
+                    }
+                    for callback in &self.bell_callbacks {
+                        callback(window, cx);
+                    }
+                }
+                TerminalEvent::Title(title) => {
+                    self.title = title;
+                    self.title_resumed = false;
+                    if self.title_override.is_none() {
+                        for callback in &self.title_callbacks {
+                            callback(window, cx, &self.title);
+                        }
+                    }
+                }
+                TerminalEvent::ClipboardStore(text) => {
+                    for callback in &self.clipboard_store_callbacks {
+                        callback(window, cx, &text);
+                    }
+                }
+                TerminalEvent::ClipboardLoad(formatter) => {
+                    let text = self
+                        .clipboard_load_callback
+                        .as_ref()
+                        .and_then(|callback| callback(window, cx));
+                    if let Some(text) = text {
+                        self.send_input(formatter.format(&text).as_bytes(), cx);
+                    }
+                }
+                TerminalEvent::PtyWrite(reply) => {
+                    self.send_input(reply.as_bytes(), cx);
+                }
+                TerminalEvent::Exit => {
+                    self.exited = true;
+                    self.renderer.exit_indicator = self.config.show_exit_indicator;
+                    for callback in &self.exit_callbacks {
+                        callback(window, cx);
+                    }
+                }
+            }
+        }
+
+        if let Some(ref callback) = self.unhandled_sequence_callback {
+            for sequence in self.state.take_unhandled_sequences() {
+                callback(window, cx, &sequence);
+            }
+        }
+
+        if self.content_changed {
+            self.content_changed = false;
+            for callback in &self.update_callbacks {
+                callback(window, cx);
+            }
+        }
+    }
+
+    /// Start a search over the terminal contents, including scrollback.
+    ///
+    /// The query is interpreted as a regular expression (see
+    /// [`TerminalState::search`]). The match closest to the bottom of the screen
+    /// is focused, the display is scrolled to it, and all matches are highlighted.
+    /// Matches are refreshed automatically as new output arrives, at most once
+    /// per frame, and focus stays on the same match while output scrolls.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The pattern to search for
+    /// * `cx` - The context for triggering a repaint
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Bind Ctrl+F / Enter / Shift+Enter in the host application
+    /// terminal.update(cx, |terminal, cx| terminal.start_search("error", cx));
+    /// terminal.update(cx, |terminal, cx| terminal.next_match(cx));
+    /// terminal.update(cx, |terminal, cx| terminal.prev_match(cx));
+    /// ```
+    pub fn start_search(&mut self, query: impl Into<String>, cx: &mut Context<Self>) {
+        let query = query.into();
+        let matches = self.state.search(&query);
+        let current = matches.len().checked_sub(1);
+
+        self.search = Some(SearchState {
+            query,
+            matches,
+            current,
+            stale: false,
+        });
+        self.focus_current_match();
+        cx.notify();
+    }
+
+    /// Focus the next search match (towards the bottom of the screen), wrapping around.
+    ///
+    /// Does nothing if no search is active or there are no matches.
+    pub fn next_match(&mut self, cx: &mut Context<Self>) {
+        if let Some(search) = self.search.as_mut()
+            && !search.matches.is_empty()
+        {
+            let len = search.matches.len();
+            search.current = Some(search.current.map_or(0, |idx| (idx + 1) % len));
+            self.focus_current_match();
+            cx.notify();
+        }
+    }
+
+    /// Focus the previous search match (towards the scrollback), wrapping around.
+    ///
+    /// Does nothing if no search is active or there are no matches.
+    pub fn prev_match(&mut self, cx: &mut Context<Self>) {
+        if let Some(search) = self.search.as_mut()
+            && !search.matches.is_empty()
+        {
+            let len = search.matches.len();
+            search.current = Some(search.current.map_or(len - 1, |idx| (idx + len - 1) % len));
+            self.focus_current_match();
+            cx.notify();
+        }
+    }
+
+    /// End the active search and remove its highlights.
+    pub fn clear_search(&mut self, cx: &mut Context<Self>) {
+        self.search = None;
+        self.state.set_anchor(None);
+        self.renderer.search_matches.clear();
+        self.renderer.active_match = None;
+        cx.notify();
+    }
+
+    /// Get the query of the active search, if any.
+    pub fn search_query(&self) -> Option<&str> {
+        self.search.as_ref().map(|search| search.query.as_str())
+    }
+
+    /// Get all matches of the active search.
+    ///
+    /// # Returns
+    ///
+    /// The matches ordered from oldest scrollback line to screen bottom, or an
+    /// empty slice if no search is active.
+    pub fn search_matches(&self) -> &[Match] {
+        self.search
+            .as_ref()
+            .map_or(&[], |search| search.matches.as_slice())
+    }
+
+    /// Get the index of the focused match within [`search_matches`](Self::search_matches).
+    pub fn current_match_index(&self) -> Option<usize> {
+        self.search.as_ref().and_then(|search| search.current)
+    }
+
+    /// Re-run the active search after the grid changed, keeping the focused match.
+    ///
+    /// The focused match is found again by its grid point, which the terminal
+    /// moves along as output scrolls (see [`TerminalState::set_anchor`]), so
+    /// focus stays on the same text while lines are added or rotate out of
+    /// the scrollback. If that text is gone, the nearest match is focused.
+    fn refresh_search(&mut self) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+
+        search.stale = false;
+        search.matches = self.state.search(&search.query);
+        search.current = match self.state.anchor() {
+            Some(anchor) => nearest_match(&search.matches, anchor),
+            None => search
+                .current
+                .map(|idx| idx.min(search.matches.len().saturating_sub(1))),
+        }
+        .or(search.matches.len().checked_sub(1))
+        .filter(|_| !search.matches.is_empty());
+
+        let active_match = search.current.map(|idx| search.matches[idx].clone());
+        self.state.set_anchor(
+            active_match
+                .as_ref()
+                .map(|active| (*active.start(), *active.end())),
+        );
+        self.renderer.search_matches = search.matches.clone();
+        self.renderer.active_match = active_match;
+    }
+
+    /// Sync highlights with the renderer and scroll the focused match into view.
+    fn focus_current_match(&mut self) {
+        let Some(search) = self.search.as_ref() else {
+            return;
+        };
+
+        let active_match = search.current.map(|idx| search.matches[idx].clone());
+        if let Some(ref active_match) = active_match {
+            let point = *active_match.start();
+            self.state.with_term_mut(|term| term.scroll_to_point(point));
+        }
+        self.state.set_anchor(
+            active_match
+                .as_ref()
+                .map(|active| (*active.start(), *active.end())),
+        );
+
+        self.renderer.search_matches = search.matches.clone();
+        self.renderer.active_match = active_match;
+    }
+
+    /// Start or stop the blink timer to match the current configuration.
+    fn restart_cursor_blink(&mut self, cx: &mut Context<Self>) {
+        self.renderer.cursor_visible = true;
+        if !self.config.cursor_blink {
+            self.cursor_blink_task = None;
+            return;
+        }
+
+        let interval = self
+            .config
+            .cursor_blink_interval
+            .max(Duration::from_millis(50));
+        self.cursor_blink_task = Some(cx.spawn(
+            async move |this: WeakEntity<Self>, cx: &mut AsyncApp| loop {
+                cx.background_executor().timer(interval).await;
+                if this
+                    .update(cx, |view: &mut Self, cx: &mut Context<Self>| {
+                        view.blink_cursor(cx)
+                    })
+                    .is_err()
+                {
+                    break;
+                }
+            },
+        ));
+    }
+
+    /// Advance the cursor blink by one phase.
+    ///
+    /// The cursor is held solid while unfocused and for one interval after the
+    /// last key press or click.
+    fn blink_cursor(&mut self, cx: &mut Context<Self>) {
+        let visible = if self.renderer.cursor_state != CursorState::Focused
+            || self.last_activity.elapsed() < self.config.cursor_blink_interval
+        {
+            true
+        } else {
+            !self.renderer.cursor_visible
+        };
+
+        if visible != self.renderer.cursor_visible {
+            self.renderer.cursor_visible = visible;
+            cx.notify();
+        }
+    }
+
+    /// Pick the cursor style from focus and how recently the user interacted,
+    /// and apply [`TerminalConfig::inactive_dim`] while unfocused.
+    ///
+    /// While the cursor shows the "recent" style, a repaint is scheduled for
+    /// the moment it should switch to idle.
+    fn update_cursor_state(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let idle_in = self
+            .config
+            .cursor_idle_timeout
+            .checked_sub(self.last_activity.elapsed())
+            .filter(|remaining| !remaining.is_zero());
+
+        let focused = self.focus_handle.is_focused(window);
+        self.renderer.dim_overlay = self.config.inactive_dim.filter(|_| !focused);
+        self.renderer.cursor_state = if self.exited {
+            CursorState::Idle
+        } else if focused {
+            CursorState::Focused
+        } else if idle_in.is_some() {
+            CursorState::Recent
+        } else {
+            CursorState::Idle
+        };
+
+        self.cursor_idle_task = match (self.renderer.cursor_state, idle_in) {
+            (CursorState::Recent, Some(remaining)) => Some(cx.spawn(
+                async move |this: WeakEntity<Self>, cx: &mut AsyncApp| {
+                    cx.background_executor().timer(remaining).await;
+                    let _ = this.update(cx, |_view, cx: &mut Context<Self>| cx.notify());
+                },
+            )),
+            _ => None,
+        };
+    }
+
+    /// Get the current terminal dimensions.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of (columns, rows).
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.state.cols(), self.state.rows())
+    }
+
+    /// Get the cell size measured during the last paint.
+    ///
+    /// Use this to size a container to an exact number of rows or columns,
+    /// e.g. `rows * cell_size.height` plus vertical padding.
+    ///
+    /// # Returns
+    ///
+    /// The width and height of one cell, or `None` before the first paint.
+    pub fn cell_size(&self) -> Option<Size<Pixels>> {
+        self.grid_layout.lock().map(|layout| Size {
+            width: layout.cell_width,
+            height: layout.cell_height,
+        })
+    }
+
+    /// Get the pixel rectangle the grid's cells covered in the last paint.
+    ///
+    /// This is the area inside the padding, minus the space left over by
+    /// partial cells, in window coordinates.
+    ///
+    /// # Returns
+    ///
+    /// The bounds of the cells, or `None` before the first paint.
+    pub fn content_bounds(&self) -> Option<Bounds<Pixels>> {
+        self.grid_layout
+            .lock()
+            .map(|layout| layout.content_bounds())
+    }
+
+    /// Get the number of scrollback lines stored above the screen.
+    ///
+    /// Together with [`display_offset`](Self::display_offset) this is enough to
+    /// size and position a scrollbar. It is `0` on the alternate screen, where
+    /// a scrollbar should be hidden.
+    pub fn history_size(&self) -> usize {
+        self.state.history_size()
+    }
+
+    /// Get how many lines the viewport is scrolled up into the scrollback.
+    pub fn display_offset(&self) -> usize {
+        self.state.display_offset()
+    }
+
+    /// Check whether a grid point is currently on screen.
     ///
-    /// This function runs in a background thread, continuously reading bytes
-    /// from the stdout reader and sending them through the async channel.
-    /// The async channel allows the main async task to be woken up immediately
-    /// when data arrives (push-based).
-    fn read_stdout_blocking<R: Read + Send + 'static>(
-        mut stdout_reader: R,
-        bytes_tx: flume::Sender<Vec<u8>>,
-    ) {
-        let mut buffer = [0u8; 4096];
+    /// `point` is in grid coordinates like selections and search matches:
+    /// line `0` is the top of the screen when not scrolled, and negative lines
+    /// are in the scrollback. Use this to decide whether to scroll to a search
+    /// match, or to skip drawing something for a point scrolled out of view.
+    pub fn is_point_visible(&self, point: AlacPoint) -> bool {
+        self.state.to_screen_point(point).is_some()
+    }
 
-        loop {
-            match stdout_reader.read(&mut buffer) {
-                Ok(0) => {
-                    // EOF - channel will be dropped, signaling completion
-                    break;
-                }
-                Ok(n) => {
-                    // Send bytes to the async task
-                    let bytes = buffer[..n].to_vec();
-                    if bytes_tx.send(bytes).is_err() {
-                        break; // Channel closed
-                    }
-                }
-                Err(_) => {
-                    // Read error
-                    break;
-                }
-            }
+    /// Get the current terminal mode flags.
+    ///
+    /// Useful for integrations that need modes without a convenience
+    /// predicate, e.g. `mode().contains(TermMode::ALT_SCREEN)`.
+    pub fn mode(&self) -> TermMode {
+        self.state.mode()
+    }
+
+    /// Get the lines prompts started on, from OSC 133 shell integration marks.
+    ///
+    /// Lines are counted from the top of the scrollback; see
+    /// [`TerminalState::shell_marks`] for the details and limitations. Empty if
+    /// the shell doesn't emit OSC 133.
+    pub fn prompt_lines(&self) -> Vec<usize> {
+        self.state.prompt_lines()
+    }
+
+    /// Scroll the previous prompt above the top of the viewport into view.
+    ///
+    /// The prompt line becomes the first visible row. Needs a shell that emits
+    /// OSC 133 prompt marks.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the display scrolled, `false` if there is no earlier prompt.
+    pub fn jump_to_previous_prompt(&mut self, cx: &mut Context<Self>) -> bool {
+        let top = self.state.history_size() - self.state.display_offset();
+        let target = self
+            .state
+            .prompt_lines()
+            .into_iter()
+            .rev()
+            .find(|&line| line < top);
+        match target {
+            Some(line) => self.scroll_line_to_top(line, cx),
+            None => false,
         }
     }
 
-    /// Handle keyboard input events.
+    /// Scroll the next prompt below the top of the viewport into view.
     ///
-    /// Converts GPUI keystrokes to terminal escape sequences and writes them
-    /// to the stdin writer. If a key handler is set and returns true, the event
-    /// is consumed and not sent to the terminal.
-    fn on_key_down(&mut self, event: &KeyDownEvent, _window: &mut Window, _cx: &mut Context<Self>) {
-        // Check if key handler wants to consume this event
-        if let Some(ref handler) = self.key_handler
-            && handler(event)
-        {
-            return; // Event consumed by handler
+    /// The prompt line becomes the first visible row, or the display returns
+    /// to the bottom if the prompt is on the screen.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the display scrolled, `false` if there is no later prompt.
+    pub fn jump_to_next_prompt(&mut self, cx: &mut Context<Self>) -> bool {
+        let top = self.state.history_size() - self.state.display_offset();
+        let target = self
+            .state
+            .prompt_lines()
+            .into_iter()
+            .find(|&line| line > top);
+        match target {
+            Some(line) => self.scroll_line_to_top(line, cx),
+            None => false,
         }
+    }
 
-        if let Some(bytes) = keystroke_to_bytes(&event.keystroke, self.state.mode()) {
-            let mut writer = self.stdin_writer.lock();
-            let _ = writer.write_all(&bytes);
-            let _ = writer.flush();
+    /// Scroll so a line (counted from the top of the scrollback) is the first
+    /// visible row, as far as the scrollback allows.
+    fn scroll_line_to_top(&mut self, line: usize, cx: &mut Context<Self>) -> bool {
+        let current = self.state.display_offset();
+        let target = self.state.history_size().saturating_sub(line);
+        if target == current {
+            return false;
         }
+        self.scroll_display(Scroll::Delta(target as i32 - current as i32), cx);
+        true
     }
 
-    /// Handle mouse down events.
+    /// Whether the program wants the cursor shown (DECTCEM).
     ///
-    /// Currently a placeholder for future mouse selection and interaction support.
-    fn on_mouse_down(
-        &mut self,
-        _event: &MouseDownEvent,
-        window: &mut Window,
-        cx: &mut Context<Self>,
-    ) {
-        // Request focus when clicking the terminal
-        window.focus(&self.focus_handle);
-        cx.notify();
+    /// The cursor isn't painted while the program keeps it hidden with
+    /// `CSI ? 25 l`.
+    pub fn cursor_visible(&self) -> bool {
+        self.state.cursor_visible()
+    }
 
-        // TODO: Implement mouse selection
-        // - Convert pixel coordinates to cell coordinates
-        // - Start selection at clicked cell
-        // - Send mouse reports if mouse tracking is enabled
+    /// Whether the bell rang while the terminal was unfocused.
+    ///
+    /// The flag stays set until the terminal is focused or
+    /// [`acknowledge_bell`](Self::acknowledge_bell) is called, which makes it
+    /// suitable for urgency hints and tab decorations. Bells are picked up
+    /// when the view renders, and bells while focused don't set it.
+    pub fn bell_pending(&self) -> bool {
+        self.bell_pending
     }
 
-    /// Handle mouse up events.
+    /// Whether the process has exited.
     ///
-    /// Currently a placeholder for future mouse selection support.
-    fn on_mouse_up(
-        &mut self,
-        _event: &MouseUpEvent,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
-    ) {
-        // TODO: Implement mouse selection
-        // - End selection at released cell
-        // - Copy selection to clipboard if configured
+    /// Set when the exit event is processed, i.e. when the view renders after
+    /// the PTY output reached EOF. From then on the cursor is dimmed and input
+    /// is dropped instead of being written to the closed PTY.
+    pub fn has_exited(&self) -> bool {
+        self.exited
     }
 
-    /// Handle mouse move events.
+    /// Clear the pending bell flag without focusing the terminal.
+    pub fn acknowledge_bell(&mut self) {
+        self.bell_pending = false;
+    }
+
+    /// The title to display for this terminal.
     ///
-    /// Currently a placeholder for future mouse selection support.
-    fn on_mouse_move(
-        &mut self,
-        _event: &MouseMoveEvent,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
-    ) {
-        // TODO: Implement mouse selection
-        // - Update selection range while dragging
-        // - Send mouse motion reports if mouse tracking is enabled
+    /// This is the override from [`set_title_override`](Self::set_title_override)
+    /// if there is one, and otherwise the title the program last set with
+    /// OSC 0 or OSC 2 (empty if it never set one or reset it).
+    pub fn title(&self) -> &str {
+        self.title_override.as_deref().unwrap_or(&self.title)
     }
 
-    /// Handle scroll events.
+    /// Show a host-chosen title instead of the program's.
     ///
-    /// Currently a placeholder for future scrollback support.
-    fn on_scroll(
-        &mut self,
-        _event: &ScrollWheelEvent,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
-    ) {
-        // TODO: Implement scrollback
-        // - Scroll the terminal display up/down
-        // - Send scroll reports if alternate screen is not active
+    /// While an override is set, [`title`](Self::title) returns it and title
+    /// callbacks don't run for the program's title changes, which are still
+    /// tracked. Passing `None` resumes following the program: the callbacks
+    /// then receive its current title on the next render, so a tab showing
+    /// the override gets updated.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The title to show, or `None` to use the program's again
+    /// * `cx` - The context for triggering a repaint
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// terminal.update(cx, |terminal, cx| {
+    ///     terminal.set_title_override(Some("[running] vim".into()), cx);
+    /// });
+    /// ```
+    pub fn set_title_override(&mut self, title: Option<String>, cx: &mut Context<Self>) {
+        self.title_resumed = title.is_none() && self.title_override.is_some();
+        self.title_override = title;
+        cx.notify();
     }
 
-    /// Process pending terminal events.
+    /// Paste text into the terminal.
     ///
-    /// This method drains all available events from the event receiver
-    /// and handles them appropriately. Note: bytes are processed in the
-    /// async reader task, not here.
-    fn process_events(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        // Process terminal events (from alacritty event proxy)
-        while let Ok(event) = self.event_rx.try_recv() {
-            match event {
-                TerminalEvent::Wakeup => {
-                    // Terminal has new content - already handled by async task
-                }
-                TerminalEvent::Bell => {
-                    if let Some(ref callback) = self.bell_callback {
-                        callback(window, cx);
-                    }
-                }
-                TerminalEvent::Title(title) => {
-                    if let Some(ref callback) = self.title_callback {
-                        callback(window, cx, &title);
-                    }
-                }
-                TerminalEvent::ClipboardStore(text) => {
-                    if let Some(ref callback) = self.clipboard_store_callback {
-                        callback(window, cx, &text);
-                    }
-                }
-                TerminalEvent::ClipboardLoad => {
-                    // Terminal wants to load data from clipboard
-                    // TODO: Implement clipboard integration
-                }
-                TerminalEvent::Exit => {
-                    if let Some(ref callback) = self.exit_callback {
-                        callback(window, cx);
-                    }
-                }
-            }
+    /// The text is wrapped for bracketed paste when the program enabled it,
+    /// and sanitized otherwise if [`TerminalConfig::sanitize_paste`] is set.
+    /// See [`paste_to_bytes`](crate::input::paste_to_bytes).
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to paste, e.g. from [`Clipboard::paste`](crate::Clipboard::paste)
+    /// * `cx` - The context used to write the input
+    pub fn paste(&mut self, text: &str, cx: &mut Context<Self>) {
+        let bytes = paste_to_bytes(
+            text,
+            self.bracketed_paste_enabled(),
+            self.config.sanitize_paste,
+        );
+        self.send_input(&bytes, cx);
+    }
+
+    /// Whether the program enabled bracketed paste (DECSET 2004).
+    ///
+    /// When enabled, pasted text should be wrapped in `ESC [200~` and `ESC [201~`
+    /// so the program can tell it apart from typed input. When disabled, any
+    /// newline in pasted text executes like a typed Enter, so embedders may want
+    /// to confirm multi-line pastes first.
+    pub fn bracketed_paste_enabled(&self) -> bool {
+        self.state.mode().contains(TermMode::BRACKETED_PASTE)
+    }
+
+    /// Scroll the display through the scrollback.
+    ///
+    /// Does nothing on the alternate screen.
+    ///
+    /// # Arguments
+    ///
+    /// * `scroll` - How far to scroll, e.g. `Scroll::Delta(3)` or `Scroll::Bottom`
+    /// * `cx` - The context for triggering a repaint
+    pub fn scroll_display(&mut self, scroll: Scroll, cx: &mut Context<Self>) {
+        // The alternate screen has no scrollback to move through
+        if self.state.mode().contains(TermMode::ALT_SCREEN) {
+            return;
         }
+        self.state.with_term_mut(|term| term.scroll_display(scroll));
+        cx.notify();
     }
 
-    /// Get the current terminal dimensions.
+    /// Drop the scrollback history, keeping the screen contents and cursor.
     ///
-    /// # Returns
+    /// The display returns to the bottom. A selection reaching into the
+    /// scrollback is cleared and search matches are recomputed.
+    pub fn clear_scrollback(&mut self, cx: &mut Context<Self>) {
+        self.state.clear_scrollback();
+
+        let in_scrollback = self
+            .renderer
+            .selection
+            .as_ref()
+            .is_some_and(|selection| selection.start.line < 0 || selection.end.line < 0);
+        if in_scrollback {
+            self.reset_selection();
+        }
+        self.refresh_search();
+        cx.notify();
+    }
+
+    /// Pause or resume processing of PTY output.
     ///
-    /// A tuple of (columns, rows).
-    pub fn dimensions(&self) -> (usize, usize) {
-        (self.state.cols(), self.state.rows())
+    /// While paused the screen stops updating and output stays queued: a few
+    /// reads in the reader channel, then in the PTY buffer, and once that
+    /// fills, the program writing to it blocks. Resuming processes the queued output in order. Input is still
+    /// sent while paused.
+    ///
+    /// # Arguments
+    ///
+    /// * `paused` - `true` to freeze the screen, `false` to resume
+    pub fn set_paused(&mut self, paused: bool) {
+        if self.paused && !paused {
+            let _ = self.resume_tx.send(());
+        }
+        self.paused = paused;
+    }
+
+    /// Whether PTY output processing is currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
     }
 
     /// Resize the terminal to new dimensions.
@@ -886,40 +3264,173 @@ impl TerminalView {
         self.renderer.font_size = config.font_size;
         self.renderer.line_height_multiplier = config.line_height_multiplier;
         self.renderer.palette = config.colors.clone();
+        self.renderer.letter_spacing = config.letter_spacing;
+        self.renderer.cell_width =
+            config.font_size * config.cell_width_ratio + config.letter_spacing;
+        self.renderer.cell_height = config.font_size * config.cell_height_ratio;
+        self.renderer.synthesize_italic = config.synthesize_italic;
+        self.renderer.use_builtin_box_drawing = config.use_builtin_box_drawing;
+        self.renderer.cursor_inverts_text = config.cursor_inverts_text;
+        self.renderer.forced_background = config.forced_background;
+        self.renderer.draw_cell_grid = config.draw_cell_grid;
+        self.renderer.column_guides = config.column_guides.clone();
+        self.renderer.exit_indicator = self.exited && config.show_exit_indicator;
+        self.renderer.show_whitespace = config.show_whitespace;
+        self.renderer.cursor_follows_fg = config.cursor_follows_fg;
+        self.renderer.bar_cursor_width = config.bar_cursor_width;
+        self.state
+            .set_show_control_pictures(config.show_control_pictures);
+        self.state.set_config(config.alacritty_config());
 
         // Store the new config
+        let blink_changed = config.cursor_blink != self.config.cursor_blink
+            || config.cursor_blink_interval != self.config.cursor_blink_interval;
         self.config = config;
+        if blink_changed {
+            self.restart_cursor_blink(cx);
+        }
 
         // Trigger a repaint - cell dimensions will be recalculated via measure_cell()
         cx.notify();
     }
 
+    /// Grow or shrink the font, keeping it within
+    /// [`min_font_size`](TerminalConfig::min_font_size) and
+    /// [`max_font_size`](TerminalConfig::max_font_size).
+    ///
+    /// Used for Ctrl+scroll zooming, and handy for zoom shortcuts and gestures
+    /// in the host application.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - The change in font size; negative values shrink the font
+    /// * `cx` - The context for triggering a repaint
+    pub fn adjust_font_size(&mut self, delta: Pixels, cx: &mut Context<Self>) {
+        let min = f32::from(self.config.min_font_size);
+        let max = f32::from(self.config.max_font_size).max(min);
+        let font_size = px(f32::from(self.config.font_size + delta).clamp(min, max));
+        if font_size != self.config.font_size {
+            let mut config = self.config.clone();
+            config.font_size = font_size;
+            self.update_config(config, cx);
+        }
+    }
+
     /// Calculate terminal dimensions from pixel bounds and cell size.
     ///
     /// Helper method to determine how many columns and rows fit in the given bounds.
     #[allow(dead_code)]
     fn calculate_dimensions(&self, bounds: Bounds<Pixels>) -> (usize, usize) {
-        let width_f32: f32 = bounds.size.width.into();
-        let height_f32: f32 = bounds.size.height.into();
-        let cell_width_f32: f32 = self.renderer.cell_width.into();
-        let cell_height_f32: f32 = self.renderer.cell_height.into();
+        let cell = size(self.renderer.cell_width, self.renderer.cell_height);
+        grid_dimensions_for(bounds.size, cell, Edges::default())
+    }
+}
 
-        let cols = ((width_f32 / cell_width_f32) as usize).max(1);
-        let rows = ((height_f32 / cell_height_f32) as usize).max(1);
-        (cols, rows)
+/// List the dynamic colors that differ between two color tables.
+///
+/// Entries past the cursor color (alacritty's derived dim colors) aren't
+/// settable by programs and are skipped.
+fn color_changes(old: &Colors, new: &Colors) -> Vec<(DynamicColor, Option<Rgb>)> {
+    (0..=NamedColor::Cursor as usize)
+        .filter(|&index| old[index] != new[index])
+        .map(|index| {
+            let color = match index {
+                0..=255 => DynamicColor::Indexed(index as u8),
+                index if index == NamedColor::Foreground as usize => DynamicColor::Foreground,
+                index if index == NamedColor::Background as usize => DynamicColor::Background,
+                _ => DynamicColor::Cursor,
+            };
+            (color, new[index])
+        })
+        .collect()
+}
+
+/// Find the match starting at `anchor`, or else the one starting on the line
+/// nearest to it, preferring the earlier match on a tie.
+///
+/// `matches` must be ordered by their start, as search returns them.
+fn nearest_match(matches: &[Match], anchor: AlacPoint) -> Option<usize> {
+    let after = matches.partition_point(|found| *found.start() < anchor);
+    let before = after.checked_sub(1);
+    let after = (after < matches.len()).then_some(after);
+    let distance = |idx: usize| (matches[idx].start().line - anchor.line).0.unsigned_abs();
+    match (before, after) {
+        (Some(before), Some(after)) => {
+            let at_anchor = *matches[after].start() == anchor;
+            Some(if at_anchor || distance(after) < distance(before) {
+                after
+            } else {
+                before
+            })
+        }
+        _ => before.or(after),
+    }
+}
+
+/// Move the keyboard selection cursor for a movement key, staying within the
+/// columns and `lines` of the grid. Returns `None` for other keys.
+fn step_selection_cursor(
+    point: AlacPoint,
+    key: &str,
+    cols: usize,
+    lines: std::ops::RangeInclusive<AlacLine>,
+) -> Option<AlacPoint> {
+    let AlacPoint { line, column } = point;
+    let point = match key {
+        "left" => AlacPoint::new(line, AlacColumn(column.0.saturating_sub(1))),
+        "right" => AlacPoint::new(line, AlacColumn((column.0 + 1).min(cols - 1))),
+        "up" => AlacPoint::new((line - 1i32).max(*lines.start()), column),
+        "down" => AlacPoint::new((line + 1i32).min(*lines.end()), column),
+        "home" => AlacPoint::new(line, AlacColumn(0)),
+        "end" => AlacPoint::new(line, AlacColumn(cols - 1)),
+        _ => return None,
+    };
+    Some(point)
+}
+
+/// Ring the bell of the terminal the host process runs in.
+///
+/// Writes BEL to `/dev/tty` where there is one, and otherwise to stdout if it
+/// is a terminal. Failures are ignored: a missing beep isn't worth reporting.
+#[cfg(feature = "audible-bell")]
+fn system_beep() {
+    use std::io::IsTerminal;
+
+    #[cfg(unix)]
+    if let Ok(mut tty) = std::fs::OpenOptions::new().write(true).open("/dev/tty") {
+        let _ = tty.write_all(b"\x07");
+        return;
+    }
+
+    let mut stdout = std::io::stdout();
+    if stdout.is_terminal() {
+        let _ = stdout.write_all(b"\x07");
+        let _ = stdout.flush();
     }
 }
 
 impl Render for TerminalView {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // Catch up with the resize of the last paint
+        self.state.sync_size();
+
+        // Matches go stale with output, and are refreshed at most once a frame
+        if self.search.as_ref().is_some_and(|search| search.stale) {
+            self.refresh_search();
+        }
+
         // Process any pending events
         self.process_events(window, cx);
+        self.update_cursor_state(window, cx);
 
         // Get terminal state and renderer for rendering
         let state_arc = self.state.term_arc();
         let renderer = self.renderer.clone();
         let resize_callback = self.resize_callback.clone();
+        let layout_callback = self.layout_callback.clone();
         let padding = self.config.padding;
+        let grid_layout = self.grid_layout.clone();
+        let center_grid = self.config.center_grid;
 
         div()
             .size_full()
@@ -934,8 +3445,6 @@ impl Render for TerminalView {
                 canvas(
                     move |bounds, _window, _cx| bounds,
                     move |bounds, _, window, cx| {
-                        use alacritty_terminal::grid::Dimensions;
-
                         // Measure actual cell dimensions from the font
                         let mut measured_renderer = renderer.clone();
                         measured_renderer.measure_cell(window);
@@ -948,8 +3457,9 @@ impl Render for TerminalView {
                         let cell_width_f32: f32 = measured_renderer.cell_width.into();
                         let cell_height_f32: f32 = measured_renderer.cell_height.into();
 
-                        let cols = ((available_width / cell_width_f32) as usize).max(1);
-                        let rows = ((available_height / cell_height_f32) as usize).max(1);
+                        let cell =
+                            size(measured_renderer.cell_width, measured_renderer.cell_height);
+                        let (cols, rows) = grid_dimensions_for(bounds.size, cell, padding);
 
                         // Helper struct implementing Dimensions for resize
                         struct TermSize {
@@ -989,8 +3499,41 @@ impl Render for TerminalView {
                             term.resize(TermSize { cols, rows });
                         }
 
+                        // Spread the space left over by partial cells evenly around the grid
+                        let mut padding = padding;
+                        if center_grid {
+                            let extra_x =
+                                px((available_width - cols as f32 * cell_width_f32).max(0.0) / 2.0);
+                            let extra_y = px((available_height - rows as f32 * cell_height_f32)
+                                .max(0.0)
+                                / 2.0);
+                            padding.left += extra_x;
+                            padding.right += extra_x;
+                            padding.top += extra_y;
+                            padding.bottom += extra_y;
+                        }
+
+                        // Remember where cells landed for mouse hit-testing
+                        let layout = GridLayout {
+                            origin: point(
+                                bounds.origin.x + padding.left,
+                                bounds.origin.y + padding.top,
+                            ),
+                            cols,
+                            rows,
+                            cell_width: measured_renderer.cell_width,
+                            cell_height: measured_renderer.cell_height,
+                            scale_factor: measured_renderer.scale_factor,
+                        };
+                        let layout_changed = grid_layout.lock().replace(layout) != Some(layout);
+
                         // Paint the terminal with measured dimensions
                         measured_renderer.paint(bounds, padding, &term, window, cx);
+                        drop(term);
+
+                        if layout_changed && let Some(ref callback) = layout_callback {
+                            callback(cols, rows, layout.content_bounds());
+                        }
                     },
                 )
                 .size_full(),
@@ -998,5 +3541,421 @@ impl Render for TerminalView {
     }
 }
 
-// Tests are omitted due to macro expansion issues with the test attribute
-// in this configuration. Integration tests can be added separately.
+#[cfg(test)]
+mod tests {
+    use super::{
+        Decorations, DynamicColor, GridLayout, ScrollOnOutput, TerminalConfig, TerminalView,
+        color_changes, nearest_match, step_selection_cursor,
+    };
+    use crate::event::GpuiEventProxy;
+    use crate::terminal::TerminalState;
+    use alacritty_terminal::grid::Scroll;
+    use gpui::{
+        AppContext, Modifiers, MouseButton, MouseDownEvent, MouseMoveEvent, ScrollDelta,
+        ScrollWheelEvent, TestAppContext, TouchPhase, point, px,
+    };
+    use std::cell::RefCell;
+    use std::io::{Read, Write};
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use std::sync::mpsc::{Receiver, channel};
+
+    /// Reader that serves its data in pieces and records each requested length.
+    struct RecordingReader {
+        data: Vec<u8>,
+        requested: Arc<parking_lot::Mutex<Vec<usize>>>,
+    }
+
+    impl Read for RecordingReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.requested.lock().push(buf.len());
+            let n = buf.len().min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data.drain(..n);
+            Ok(n)
+        }
+    }
+
+    /// Writer that collects everything written to the PTY.
+    struct SharedWriter(Arc<parking_lot::Mutex<Vec<u8>>>);
+
+    impl Write for SharedWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Reader that blocks until its sender is dropped, like an idle PTY.
+    struct IdleReader(Receiver<()>);
+
+    impl Read for IdleReader {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            let _ = self.0.recv();
+            Ok(0)
+        }
+    }
+
+    #[gpui::test]
+    fn test_replies_written_without_render(cx: &mut TestAppContext) {
+        let written = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let (_pty_open, idle) = channel();
+        let view = cx.new(|cx| {
+            TerminalView::new(
+                SharedWriter(written.clone()),
+                IdleReader(idle),
+                TerminalConfig::default(),
+                cx,
+            )
+        });
+
+        // The view is never rendered, as for a background tab
+        view.update(cx, |view, cx| {
+            view.process_output(b"\x1b]0;title\x07\x1b[18t", cx)
+        });
+        assert_eq!(*written.lock(), b"\x1b[8;24;80t");
+
+        // A query inside a synchronized update is answered when the update
+        // ends, not on the repaint after it
+        written.lock().clear();
+        view.update(cx, |view, cx| {
+            view.process_output(b"\x1b[?2026h\x1b[18t", cx)
+        });
+        assert!(written.lock().is_empty());
+        view.update(cx, |view, cx| view.process_output(b"\x1b[?2026l", cx));
+        assert_eq!(*written.lock(), b"\x1b[8;24;80t");
+
+        // Other events are kept for the next render
+        view.update(cx, |view, _| {
+            assert!(matches!(
+                view.queued_events.front(),
+                Some(crate::event::TerminalEvent::Title(title)) if title == "title"
+            ));
+        });
+    }
+
+    /// The layout of an 80x24 grid of 10x20 cells painted at the origin.
+    fn painted_layout() -> GridLayout {
+        GridLayout {
+            origin: point(px(0.0), px(0.0)),
+            cols: 80,
+            rows: 24,
+            cell_width: px(10.0),
+            cell_height: px(20.0),
+            scale_factor: 1.0,
+        }
+    }
+
+    #[gpui::test]
+    fn test_ctrl_scroll_reported_while_mouse_tracked(cx: &mut TestAppContext) {
+        let written = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let (_pty_open, idle) = channel();
+        let (view, cx) = cx.add_window_view(|_, cx| {
+            TerminalView::new(
+                SharedWriter(written.clone()),
+                IdleReader(idle),
+                TerminalConfig::default(),
+                cx,
+            )
+        });
+        let event = ScrollWheelEvent {
+            position: point(px(5.0), px(5.0)),
+            delta: ScrollDelta::Lines(point(0.0, 1.0)),
+            modifiers: Modifiers::control(),
+            touch_phase: TouchPhase::Moved,
+        };
+
+        cx.update(|window, cx| {
+            view.update(cx, |view, cx| {
+                *view.grid_layout.lock() = Some(painted_layout());
+                let font_size = view.config.font_size;
+
+                view.process_output(b"\x1b[?1000h\x1b[?1006h", cx);
+                view.on_scroll(&event, window, cx);
+                assert_eq!(view.config.font_size, font_size);
+                assert_eq!(*written.lock(), b"\x1b[<80;1;1M");
+
+                // Zooms again once the program stops tracking the mouse
+                view.process_output(b"\x1b[?1000l", cx);
+                view.on_scroll(&event, window, cx);
+                assert_eq!(view.config.font_size, font_size + px(1.0));
+            })
+        });
+    }
+
+    #[gpui::test]
+    fn test_click_selects_nothing_until_dragged(cx: &mut TestAppContext) {
+        let (_pty_open, idle) = channel();
+        let (view, cx) = cx.add_window_view(|_, cx| {
+            TerminalView::new(
+                std::io::sink(),
+                IdleReader(idle),
+                TerminalConfig::default(),
+                cx,
+            )
+        });
+        let position = |col: f32| point(px(col * 10.0 + 5.0), px(5.0));
+
+        cx.update(|window, cx| {
+            view.update(cx, |view, cx| {
+                view.process_output(b"hello", cx);
+                *view.grid_layout.lock() = Some(painted_layout());
+
+                let down = MouseDownEvent {
+                    button: MouseButton::Left,
+                    position: position(1.0),
+                    modifiers: Modifiers::default(),
+                    click_count: 1,
+                    first_mouse: false,
+                };
+                view.on_mouse_down(&down, window, cx);
+                assert!(view.selection().is_none());
+
+                // Moving within the clicked cell is not a drag
+                let mut drag = MouseMoveEvent {
+                    position: position(1.2),
+                    pressed_button: Some(MouseButton::Left),
+                    modifiers: Modifiers::default(),
+                };
+                view.on_mouse_move(&drag, window, cx);
+                assert!(view.selection().is_none());
+                assert_eq!(view.selection_text(), None);
+
+                drag.position = position(3.0);
+                view.on_mouse_move(&drag, window, cx);
+                assert_eq!(view.selection_text().as_deref(), Some("ell"));
+            })
+        });
+    }
+
+    #[gpui::test]
+    fn test_unhandled_sequences_reported_on_frame(cx: &mut TestAppContext) {
+        let (_pty_open, idle) = channel();
+        let reported = Rc::new(RefCell::new(Vec::new()));
+        let (view, cx) = cx.add_window_view(|_, cx| {
+            let reported = reported.clone();
+            TerminalView::new(
+                std::io::sink(),
+                IdleReader(idle),
+                TerminalConfig::default(),
+                cx,
+            )
+            .with_unhandled_sequence_callback(move |_, _, sequence| {
+                reported.borrow_mut().push(sequence.to_vec());
+            })
+        });
+
+        cx.update(|window, cx| {
+            view.update(cx, |view, cx| {
+                view.process_output(b"a\x1b[?9999hb", cx);
+                view.process_events(window, cx);
+            })
+        });
+        assert_eq!(*reported.borrow(), [b"\x1b[?9999h".to_vec()]);
+    }
+
+    #[gpui::test]
+    fn test_search_keeps_focused_match(cx: &mut TestAppContext) {
+        let (_pty_open, idle) = channel();
+        let config = TerminalConfig {
+            cols: 10,
+            rows: 2,
+            scrollback: 4,
+            ..TerminalConfig::default()
+        };
+        let view = cx.new(|cx| TerminalView::new(std::io::sink(), IdleReader(idle), config, cx));
+
+        view.update(cx, |view, cx| {
+            view.process_output(b"foo 1\r\nfoo 2\r\nfoo 3\r\n", cx);
+            view.start_search("foo", cx);
+            view.prev_match(cx);
+            assert_eq!(view.current_match_index(), Some(1));
+
+            // Output only marks the matches stale
+            view.process_output(b"foo 4\r\nx\r\ny\r\n", cx);
+            assert!(view.search.as_ref().unwrap().stale);
+            assert_eq!(view.search_matches().len(), 3);
+
+            // "foo 1" rotated out of the history, and focus stays on "foo 2"
+            view.refresh_search();
+            let focused = view.search_matches()[view.current_match_index().unwrap()].clone();
+            assert_eq!(view.search_matches().len(), 3);
+            assert_eq!(focused.start(), view.state.search("foo 2")[0].start());
+        });
+    }
+
+    #[test]
+    fn test_nearest_match() {
+        let (tx, _rx) = channel();
+        let mut terminal = TerminalState::new(10, 6, GpuiEventProxy::new(tx));
+        terminal.process_bytes(b"ab\r\n\r\n\r\nab ab\r\n\r\nab");
+        let matches = terminal.search("ab");
+        let point = |line, col| {
+            alacritty_terminal::index::Point::new(
+                alacritty_terminal::index::Line(line),
+                alacritty_terminal::index::Column(col),
+            )
+        };
+
+        assert_eq!(nearest_match(&matches, point(3, 3)), Some(2));
+        assert_eq!(nearest_match(&matches, point(1, 0)), Some(0));
+        assert_eq!(nearest_match(&matches, point(2, 0)), Some(1));
+        assert_eq!(nearest_match(&matches, point(5, 9)), Some(3));
+        assert_eq!(nearest_match(&[], point(0, 0)), None);
+    }
+
+    #[test]
+    fn test_read_buffer_size() {
+        let requested = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let reader = RecordingReader {
+            data: vec![b'x'; 100],
+            requested: requested.clone(),
+        };
+        let (bytes_tx, bytes_rx) = flume::unbounded();
+
+        TerminalView::read_stdout_blocking(reader, bytes_tx, 64);
+
+        assert_eq!(*requested.lock(), [64, 64, 64]);
+        let chunks: Vec<usize> = bytes_rx.drain().map(|bytes| bytes.len()).collect();
+        assert_eq!(chunks, [64, 36]);
+    }
+
+    #[test]
+    fn test_color_changes() {
+        use alacritty_terminal::vte::ansi::Rgb;
+        use std::sync::mpsc::channel;
+
+        let mut terminal = TerminalState::new(10, 2, GpuiEventProxy::new(channel().0));
+        let changes = |terminal: &mut TerminalState, bytes: &[u8]| {
+            let old = terminal.with_term(|term| *term.colors());
+            terminal.process_bytes(bytes);
+            color_changes(&old, &terminal.with_term(|term| *term.colors()))
+        };
+        let white = Rgb {
+            r: 0xff,
+            g: 0xff,
+            b: 0xff,
+        };
+
+        assert!(changes(&mut terminal, b"plain \x1b[31mred").is_empty());
+        assert_eq!(
+            changes(&mut terminal, b"\x1b]4;1;#ffffff\x07\x1b]11;#ffffff\x07"),
+            [
+                (DynamicColor::Indexed(1), Some(white)),
+                (DynamicColor::Background, Some(white))
+            ]
+        );
+
+        // Setting the same value again isn't a change
+        assert!(changes(&mut terminal, b"\x1b]11;#ffffff\x07").is_empty());
+
+        assert_eq!(
+            changes(&mut terminal, b"\x1b]12;#ffffff\x07\x1b]104;1\x07"),
+            [
+                (DynamicColor::Indexed(1), None),
+                (DynamicColor::Cursor, Some(white))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decorations_is_empty() {
+        assert!(Decorations::default().is_empty());
+        let search = Decorations {
+            search: true,
+            ..Decorations::default()
+        };
+        assert!(!search.is_empty());
+    }
+
+    #[test]
+    fn test_content_bounds() {
+        use gpui::{point, px, size};
+
+        let layout = GridLayout {
+            origin: point(px(12.0), px(8.0)),
+            cols: 80,
+            rows: 24,
+            cell_width: px(8.5),
+            cell_height: px(17.0),
+            scale_factor: 1.0,
+        };
+        let bounds = layout.content_bounds();
+        assert_eq!(bounds.origin, point(px(12.0), px(8.0)));
+        assert_eq!(bounds.size, size(px(680.0), px(408.0)));
+
+        // Fractional cells end on the snapped edge the renderer paints
+        let layout = GridLayout {
+            cols: 101,
+            cell_width: px(7.3),
+            scale_factor: 2.0,
+            ..layout
+        };
+        let bounds = layout.content_bounds();
+        assert_eq!(bounds.right(), px(749.5));
+        assert_eq!(layout.column_x(3), px(34.0));
+    }
+
+    #[test]
+    fn test_step_selection_cursor() {
+        use alacritty_terminal::index::{Column, Line, Point};
+
+        let lines = Line(-2)..=Line(3);
+        let step = |line, column, key| {
+            step_selection_cursor(
+                Point::new(Line(line), Column(column)),
+                key,
+                10,
+                lines.clone(),
+            )
+            .map(|point| (point.line.0, point.column.0))
+        };
+        assert_eq!(step(0, 5, "left"), Some((0, 4)));
+        assert_eq!(step(0, 0, "left"), Some((0, 0)));
+        assert_eq!(step(0, 9, "right"), Some((0, 9)));
+        assert_eq!(step(-1, 5, "up"), Some((-2, 5)));
+        assert_eq!(step(-2, 5, "up"), Some((-2, 5)));
+        assert_eq!(step(3, 5, "down"), Some((3, 5)));
+        assert_eq!(step(1, 5, "home"), Some((1, 0)));
+        assert_eq!(step(1, 5, "end"), Some((1, 9)));
+        assert_eq!(step(1, 5, "a"), None);
+    }
+
+    #[gpui::test]
+    fn test_scroll_on_output(cx: &mut TestAppContext) {
+        // Show "1" to "4" in a 10x2 view, optionally scroll up a line, then
+        // print two more lines and return where the display ended up
+        let display_offset = |cx: &mut TestAppContext, policy, scroll_up| {
+            let (_pty_open, idle) = channel();
+            let config = TerminalConfig {
+                cols: 10,
+                rows: 2,
+                scroll_on_output: policy,
+                ..TerminalConfig::default()
+            };
+            let view =
+                cx.new(|cx| TerminalView::new(std::io::sink(), IdleReader(idle), config, cx));
+            view.update(cx, |view, cx| {
+                view.process_output(b"1\r\n2\r\n3\r\n4", cx);
+                if scroll_up {
+                    view.scroll_display(Scroll::Delta(1), cx);
+                }
+                view.process_output(b"\r\n5\r\n6", cx);
+                view.state.display_offset()
+            })
+        };
+
+        assert_eq!(display_offset(cx, ScrollOnOutput::Always, true), 0);
+        assert_eq!(display_offset(cx, ScrollOnOutput::IfAtBottom, true), 3);
+        // The scroll lock has held "1" and "2" in view since the first output
+        assert_eq!(display_offset(cx, ScrollOnOutput::Never, true), 4);
+        assert_eq!(display_offset(cx, ScrollOnOutput::Never, false), 4);
+
+        // Otherwise a display at the bottom follows the output
+        assert_eq!(display_offset(cx, ScrollOnOutput::IfAtBottom, false), 0);
+    }
+}
@@ -0,0 +1,177 @@
+//! End-to-end tests replaying recorded program output.
+//!
+//! Each fixture in `tests/fixtures` is the raw output of a real program,
+//! captured through a PTY with `script` and `TERM=xterm-256color`:
+//!
+//! | Fixture | Program | Size |
+//! |---------|---------|------|
+//! | `ls_color.bin` | `ls --color=always` in a directory with a file, an executable, a symlink and a directory | 80x24 |
+//! | `vim_startup.bin` | `vim -u NONE -c 'syntax on' -c redraw -c 'qa!' main.rs` | 40x8 |
+//! | `tput.bin` | A script of `tput` cursor movement and attribute calls | 80x24 |
+//!
+//! The bytes go through [`TerminalState::process_bytes`] like PTY output
+//! would, and the assertions check the resulting grid through the public
+//! accessors.
+
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::cell::Flags;
+use gpui_terminal::terminal::{StyledCell, TerminalState};
+use gpui_terminal::{ColorPalette, GpuiEventProxy, TermMode};
+use std::sync::mpsc::channel;
+
+const LS_COLOR: &[u8] = include_bytes!("fixtures/ls_color.bin");
+const VIM_STARTUP: &[u8] = include_bytes!("fixtures/vim_startup.bin");
+const TPUT: &[u8] = include_bytes!("fixtures/tput.bin");
+
+fn replay(cols: usize, rows: usize, bytes: &[u8]) -> TerminalState {
+    let (tx, _rx) = channel();
+    let mut terminal = TerminalState::new(cols, rows, GpuiEventProxy::new(tx));
+    terminal.process_bytes(bytes);
+    terminal
+}
+
+fn cursor(terminal: &TerminalState) -> Point {
+    terminal.with_term(|term| term.grid().cursor.point)
+}
+
+/// The cell where `text` starts on `row`.
+fn cell_at<'a>(rows: &'a [Vec<StyledCell>], row: usize, text: &str) -> &'a StyledCell {
+    let line: String = rows[row].iter().map(|cell| cell.ch).collect();
+    let col = line
+        .find(text)
+        .unwrap_or_else(|| panic!("{text:?} not on row {row}: {line:?}"));
+    &rows[row][line[..col].chars().count()]
+}
+
+/// The position of `byte_string` in the recording.
+fn offset_of(bytes: &[u8], byte_string: &[u8]) -> usize {
+    bytes
+        .windows(byte_string.len())
+        .position(|window| window == byte_string)
+        .expect("marker not in fixture")
+}
+
+#[test]
+fn test_ls_color() {
+    let terminal = replay(80, 24, LS_COLOR);
+    let palette = ColorPalette::default();
+    let ansi = palette.ansi_colors();
+
+    let text = terminal.visible_text();
+    assert_eq!(text.lines().next(), Some("README.md  build.sh  link  src"));
+    assert_eq!(cursor(&terminal), Point::new(Line(1), Column(0)));
+
+    // `01;32` executables, `01;36` symlinks and `01;34` directories
+    let rows = terminal.visible_rows(&palette);
+    let expected = [
+        ("README.md", palette.foreground()),
+        ("build.sh", ansi[2]),
+        ("link", ansi[6]),
+        ("src", ansi[4]),
+    ];
+    for (name, color) in expected {
+        let cell = cell_at(&rows, 0, name);
+        assert_eq!(cell.fg, color, "{name}");
+        assert_eq!(cell.flags.contains(Flags::BOLD), name != "README.md");
+    }
+
+    // The reset after each name keeps the separating spaces plain
+    assert_eq!(rows[0][9].fg, palette.foreground());
+    assert!(rows[0][9].flags.is_empty());
+}
+
+#[test]
+fn test_vim_startup() {
+    let palette = ColorPalette::default();
+    let extended = palette.extended_colors();
+
+    // Stop where vim starts tearing down after drawing the screen
+    let quit = offset_of(VIM_STARTUP, b"\x1b[?2004l");
+    let mut terminal = replay(40, 8, &VIM_STARTUP[..quit]);
+
+    let mode = terminal.mode();
+    assert!(mode.contains(TermMode::ALT_SCREEN));
+    assert!(mode.contains(TermMode::APP_CURSOR | TermMode::APP_KEYPAD));
+    assert!(mode.contains(TermMode::BRACKETED_PASTE | TermMode::FOCUS_IN_OUT));
+    assert!(!terminal.cursor_visible());
+    assert_eq!(cursor(&terminal), Point::new(Line(7), Column(0)));
+
+    let text = terminal.visible_text();
+    let lines: Vec<_> = text.lines().map(str::trim_end).collect();
+    assert_eq!(
+        lines,
+        [
+            "fn main() {",
+            "    println!(\"hello\");",
+            "}",
+            "~",
+            "~",
+            "~",
+            "~",
+            "\"~/fx/main.rs\" 3L, 37B",
+        ]
+    );
+
+    // Syntax colors from the 256-color and 16-color palettes
+    let rows = terminal.visible_rows(&palette);
+    let ansi = palette.ansi_colors();
+    assert_eq!(cell_at(&rows, 0, "fn").fg, extended[130]);
+    assert_eq!(cell_at(&rows, 0, "main").fg, ansi[6]);
+    assert_eq!(cell_at(&rows, 1, "println!").fg, ansi[5]);
+    assert_eq!(cell_at(&rows, 1, "\"hello\"").fg, ansi[1]);
+    assert_eq!(cell_at(&rows, 3, "~").fg, ansi[12]);
+    assert_eq!(cell_at(&rows, 0, "()").fg, palette.foreground());
+
+    // Quitting restores the primary screen, cursor and modes
+    terminal.process_bytes(&VIM_STARTUP[quit..]);
+    let mode = terminal.mode();
+    assert!(!mode.intersects(TermMode::ALT_SCREEN | TermMode::APP_CURSOR));
+    assert!(!mode.intersects(TermMode::BRACKETED_PASTE | TermMode::FOCUS_IN_OUT));
+    assert!(terminal.cursor_visible());
+    assert!(terminal.visible_text().trim().is_empty());
+}
+
+#[test]
+fn test_tput_script() {
+    let terminal = replay(80, 24, TPUT);
+    let palette = ColorPalette::default();
+    let ansi = palette.ansi_colors();
+    let extended = palette.extended_colors();
+
+    let text = terminal.visible_text();
+    let lines: Vec<_> = text.lines().map(str::trim_end).collect();
+    assert_eq!(lines[0], "top line");
+    assert_eq!(lines[2], "    error: disk full");
+    assert_eq!(lines[4], "hot rev");
+    assert_eq!(lines[6], "  italic");
+
+    // `civis` hid the cursor after the last text
+    assert!(!terminal.cursor_visible());
+    assert_eq!(cursor(&terminal), Point::new(Line(6), Column(8)));
+
+    let rows = terminal.visible_rows(&palette);
+    let error = cell_at(&rows, 2, "error");
+    assert_eq!(error.fg, ansi[1]);
+    assert!(error.flags.contains(Flags::BOLD));
+
+    // `sgr0` resets everything, `rmul` only the underline
+    let colon = cell_at(&rows, 2, ":");
+    assert_eq!(colon.fg, palette.foreground());
+    assert!(colon.flags.is_empty());
+    assert!(cell_at(&rows, 2, "disk").flags.contains(Flags::UNDERLINE));
+    assert!(!rows[2][20].flags.contains(Flags::UNDERLINE));
+
+    let hot = cell_at(&rows, 4, "hot");
+    assert_eq!((hot.fg, hot.bg), (extended[196], extended[21]));
+    assert!(cell_at(&rows, 4, "rev").flags.contains(Flags::INVERSE));
+    assert_eq!(rows[4][3].bg, palette.background());
+    assert!(cell_at(&rows, 6, "italic").flags.contains(Flags::ITALIC));
+
+    // Nothing but the drawn text changed color
+    let colored = rows
+        .iter()
+        .flatten()
+        .filter(|cell| cell.bg != palette.background())
+        .count();
+    assert_eq!(colored, 3);
+}